@@ -0,0 +1,79 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use nom::{
+    branch::alt,
+    bytes::complete::take_till1,
+    character::complete::{char, digit1, space1},
+    combinator::{map_res, value},
+    error::context,
+    sequence::{terminated, tuple},
+};
+
+use crate::parse::{self, PResult};
+use crate::{Part, Result};
+
+pub const ANSWERS_FILE: &str = "answers.txt";
+
+/// Recorded puzzle answers, stored one per line as `<day> <part> <answer>`.
+/// Blank lines and lines starting with `#` are ignored.
+#[derive(Default, Debug)]
+pub struct Answers(BTreeMap<(u32, Part), String>);
+
+fn parse_answer(input: &str) -> PResult<'_, (u32, Part, &str)> {
+    tuple((
+        context(
+            "a day number",
+            terminated(map_res(digit1, str::parse), space1),
+        ),
+        context(
+            "a part (1 or 2)",
+            terminated(
+                alt((value(Part::One, char('1')), value(Part::Two, char('2')))),
+                space1,
+            ),
+        ),
+        context("an answer", take_till1(char::is_whitespace)),
+    ))(input)
+}
+
+impl Answers {
+    /// Loads the answers at `path`; a missing file has no answers yet.
+    pub fn load(path: &Path) -> Result<Answers> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Answers::default()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut answers = Answers::default();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (day, part, answer) = parse::line(i + 1, line, parse_answer)?;
+            answers.0.insert((day, part), answer.to_string());
+        }
+        Ok(answers)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut text = String::from("# day part answer\n");
+        for ((day, part), answer) in &self.0 {
+            text += &format!("{day} {part} {answer}\n");
+        }
+        Ok(fs::write(path, text)?)
+    }
+
+    pub fn get(&self, day: u32, part: Part) -> Option<&str> {
+        self.0.get(&(day, part)).map(String::as_str)
+    }
+
+    pub fn record(&mut self, day: u32, part: Part, answer: &str) {
+        self.0.insert((day, part), answer.to_string());
+    }
+}
@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use crate::{Options, Part, Result, Solver};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// Summarizes `samples`, which must not be empty.
+    fn of(mut samples: Vec<Duration>) -> Stats {
+        samples.sort();
+        let n = samples.len();
+        let median = if n % 2 == 1 {
+            samples[n / 2]
+        } else {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        };
+        Stats {
+            min: samples[0],
+            median,
+            max: samples[n - 1],
+        }
+    }
+}
+
+/// Runs `solve` on `input` `runs` times and summarizes each phase separately:
+/// `parse`, then `part1` and `part2`.
+pub fn bench(
+    solve: Solver,
+    input: &str,
+    opts: &Options,
+    runs: usize,
+) -> Result<Vec<(&'static str, Stats)>> {
+    let runs = runs.max(1);
+    let mut parse = Vec::with_capacity(runs);
+    let mut part1 = Vec::with_capacity(runs);
+    let mut part2 = Vec::with_capacity(runs);
+
+    for _ in 0..runs {
+        let run = solve(input, &[Part::One, Part::Two], opts)?;
+        parse.push(run.parse);
+        for (part, _, elapsed) in run.answers {
+            match part {
+                Part::One => part1.push(elapsed),
+                Part::Two => part2.push(elapsed),
+            }
+        }
+    }
+
+    Ok(vec![
+        ("parse", Stats::of(parse)),
+        ("part1", Stats::of(part1)),
+        ("part2", Stats::of(part2)),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats() {
+        let ms = Duration::from_millis;
+        let s = Stats::of(vec![ms(5), ms(1), ms(3)]);
+        assert_eq!((s.min, s.median, s.max), (ms(1), ms(3), ms(5)));
+        let s = Stats::of(vec![ms(4), ms(1), ms(2), ms(9)]);
+        assert_eq!((s.min, s.median, s.max), (ms(1), ms(3), ms(9)));
+    }
+}
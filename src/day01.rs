@@ -0,0 +1,1085 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::io::{BufRead, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::{parse, Error, Options, Part, Result, Solution};
+
+pub struct Day01 {
+    digitless: Digitless,
+    /// The most edits a misspelled word may need; 0 matches words exactly.
+    fuzzy: usize,
+    /// The words to correct misspellings to, case-folded in Unicode mode.
+    lexicon: Lexicon,
+    unicode: bool,
+    /// Matches numeric digits only, for part 1.
+    digits: Automaton,
+    /// Matches numeric digits and the lexicon's words, for part 2.
+    words: Automaton,
+}
+
+impl Day01 {
+    /// With `unicode`, any Unicode decimal digit counts, and words match
+    /// regardless of case; the lexicon must then stay unambiguous once folded.
+    pub fn new(lexicon: &Lexicon, unicode: bool) -> Result<Day01> {
+        Ok(Day01 {
+            digitless: Digitless::default(),
+            fuzzy: 0,
+            lexicon: match unicode {
+                true => lexicon.folded()?,
+                false => lexicon.clone(),
+            },
+            unicode,
+            digits: Automaton::new(None, unicode),
+            words: Automaton::new(Some(lexicon), unicode),
+        })
+    }
+}
+
+/// What to do with a line that has no digit at all.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum Digitless {
+    /// Fail, naming the line.
+    #[default]
+    Error,
+    /// Leave the line out of the total.
+    Skip,
+    /// Count the line as zero.
+    Zero,
+}
+
+impl FromStr for Digitless {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Digitless> {
+        match s {
+            "error" => Ok(Digitless::Error),
+            "skip" => Ok(Digitless::Skip),
+            "zero" => Ok(Digitless::Zero),
+            _ => Err(Error::usage(format!(
+                "--digitless expects error, skip or zero, not `{s}`"
+            ))),
+        }
+    }
+}
+
+impl Default for Day01 {
+    fn default() -> Day01 {
+        Day01::new(&Lexicon::default(), false).expect("the English lexicon is valid")
+    }
+}
+
+const NUM_WORDS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+/// The spelled-out number words recognised in part 2, mapped to their values,
+/// longest first. Where "seventeen" and "seven" start at the same place, the
+/// longer word is the one that counts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lexicon(Vec<(String, u32)>);
+
+impl Default for Lexicon {
+    fn default() -> Lexicon {
+        let words = NUM_WORDS.iter().zip(1..).map(|(w, v)| (w.to_string(), v));
+        Lexicon::new(words).expect("the English lexicon is valid")
+    }
+}
+
+impl Lexicon {
+    /// Builds a lexicon, rejecting empty words, words containing digits, and
+    /// words given two different values. Repeating an entry is harmless.
+    pub fn new(entries: impl IntoIterator<Item = (String, u32)>) -> Result<Lexicon> {
+        let mut words: Vec<(String, u32)> = Vec::new();
+        for (word, value) in entries {
+            if word.is_empty() {
+                return Err(Error::invalid("lexicon words must not be empty"));
+            }
+            if word.chars().any(char::is_numeric) {
+                return Err(Error::invalid(format!(
+                    "lexicon word `{word}` must not contain digits"
+                )));
+            }
+            match words.iter().find(|(w, _)| *w == word) {
+                Some(&(_, v)) if v != value => {
+                    return Err(Error::invalid(format!(
+                        "lexicon word `{word}` is ambiguous: it means both {v} and {value}"
+                    )))
+                }
+                Some(_) => {}
+                None => words.push((word, value)),
+            }
+        }
+        words.sort_by_key(|(w, _)| std::cmp::Reverse(w.len()));
+        Ok(Lexicon(words))
+    }
+
+    /// Parses `word=value` entries separated by newlines or commas. Blank
+    /// lines and lines starting with `#` are ignored.
+    pub fn parse(text: &str) -> Result<Lexicon> {
+        let mut entries = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            if line.trim_start().starts_with('#') {
+                continue;
+            }
+            let mut start = 0;
+            for entry in line.split(',') {
+                let indent = entry.len() - entry.trim_start().len();
+                let col = parse::column(line, &line[start + indent..]);
+                start += entry.len() + 1;
+                let entry = entry.trim();
+                if entry.is_empty() {
+                    continue;
+                }
+                let (word, value) = entry
+                    .split_once('=')
+                    .ok_or_else(|| Error::parse(i + 1, col, "`word=value`", entry))?;
+                let value = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| Error::parse(i + 1, col, "a number after `=`", entry))?;
+                entries.push((word.trim().to_string(), value));
+            }
+        }
+        Lexicon::new(entries)
+    }
+
+    pub fn load(path: &Path) -> Result<Lexicon> {
+        let text = fs::read_to_string(path).map_err(|e| {
+            Error::Io(std::io::Error::new(
+                e.kind(),
+                format!("cannot read lexicon {}: {e}", path.display()),
+            ))
+        })?;
+        Lexicon::parse(&text)
+    }
+
+    /// The same lexicon with every word case-folded, as matched by
+    /// [`Automaton`] in Unicode mode.
+    pub fn folded(&self) -> Result<Lexicon> {
+        Lexicon::new(
+            self.0
+                .iter()
+                .map(|(w, v)| (w.chars().map(fold).collect(), *v)),
+        )
+    }
+
+    /// A lexicon with `other`'s entries added, validated together.
+    pub fn extend(&self, other: &Lexicon) -> Result<Lexicon> {
+        Lexicon::new(self.0.iter().chain(&other.0).cloned())
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.0.iter().map(|(w, v)| (w.as_str(), *v))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Kind {
+    Numeric,
+    Spelled,
+}
+
+/// One digit found on a calibration line: `text` starts at byte `pos`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Digit<'a> {
+    pub pos: usize,
+    pub value: u32,
+    pub kind: Kind,
+    pub text: &'a str,
+    /// The word `text` was taken for, if it is misspelled.
+    pub correction: Option<Correction<'a>>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Correction<'a> {
+    pub word: &'a str,
+    pub edits: usize,
+}
+
+impl Digit<'_> {
+    fn edits(&self) -> usize {
+        self.correction.map_or(0, |c| c.edits)
+    }
+}
+
+/// The zero of every run of ten decimal digits (General Category Nd) in
+/// Unicode 15.1, ASCII first.
+const UNICODE_ZEROS: [u32; 68] = [
+    0x0030, 0x0660, 0x06F0, 0x07C0, 0x0966, 0x09E6, 0x0A66, 0x0AE6, 0x0B66, 0x0BE6, 0x0C66, 0x0CE6,
+    0x0D66, 0x0DE6, 0x0E50, 0x0ED0, 0x0F20, 0x1040, 0x1090, 0x17E0, 0x1810, 0x1946, 0x19D0, 0x1A80,
+    0x1A90, 0x1B50, 0x1BB0, 0x1C40, 0x1C50, 0xA620, 0xA8D0, 0xA900, 0xA9D0, 0xA9F0, 0xAA50, 0xABF0,
+    0xFF10, 0x104A0, 0x10D30, 0x11066, 0x110F0, 0x11136, 0x111D0, 0x112F0, 0x11450, 0x114D0,
+    0x11650, 0x116C0, 0x11730, 0x118E0, 0x11950, 0x11C50, 0x11D50, 0x11DA0, 0x11F50, 0x16A60,
+    0x16AC0, 0x16B50, 0x1D7CE, 0x1D7D8, 0x1D7E2, 0x1D7EC, 0x1D7F6, 0x1E140, 0x1E2F0, 0x1E4F0,
+    0x1E950, 0x1FBF0,
+];
+
+/// `c` in lower case, when that is a single character of the same encoded
+/// length, so that folding never moves a match; otherwise `c` itself.
+fn fold(c: char) -> char {
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(l), None) if l.len_utf8() == c.len_utf8() => l,
+        _ => c,
+    }
+}
+
+/// A match ending at the current byte, `len` bytes long.
+#[derive(Clone, Copy, Debug)]
+struct Hit {
+    len: usize,
+    value: u32,
+    kind: Kind,
+}
+
+/// An Aho-Corasick automaton over the digits 0-9 and, optionally, the words
+/// of a lexicon. Every transition is precomputed, so each input byte costs
+/// one table lookup, and matches are reported even where they overlap.
+pub struct Automaton {
+    next: Vec<[u32; 256]>,
+    /// The matches ending in each state, longest first.
+    out: Vec<Vec<Hit>>,
+    /// Whether input is case-folded before matching.
+    fold: bool,
+}
+
+impl Automaton {
+    /// With `unicode`, matches every Unicode decimal digit rather than just
+    /// ASCII ones, and matches words case-insensitively.
+    pub fn new(lexicon: Option<&Lexicon>, unicode: bool) -> Automaton {
+        let zeros = if unicode {
+            &UNICODE_ZEROS[..]
+        } else {
+            &UNICODE_ZEROS[..1]
+        };
+        let numeric = zeros.iter().flat_map(|&zero| {
+            (0..10).filter_map(move |v| {
+                let digit = char::from_u32(zero + v)?;
+                Some((digit.to_string(), v, Kind::Numeric))
+            })
+        });
+        let spelled = lexicon.into_iter().flat_map(Lexicon::words).map(|(w, v)| {
+            let word = if unicode {
+                w.chars().map(fold).collect()
+            } else {
+                w.to_string()
+            };
+            (word, v, Kind::Spelled)
+        });
+
+        let mut trie: Vec<Vec<(u8, u32)>> = vec![Vec::new()];
+        let mut own: Vec<Option<Hit>> = vec![None];
+        for (word, value, kind) in numeric.chain(spelled) {
+            let mut state = 0;
+            for &b in word.as_bytes() {
+                state = match trie[state].iter().find(|&&(c, _)| c == b) {
+                    Some(&(_, child)) => child as usize,
+                    None => {
+                        trie.push(Vec::new());
+                        own.push(None);
+                        let child = trie.len() - 1;
+                        trie[state].push((b, child as u32));
+                        child
+                    }
+                };
+            }
+            let len = word.len();
+            own[state] = Some(Hit { len, value, kind });
+        }
+
+        // Breadth first, so a state's failure link is finished before it.
+        let mut next = vec![[0u32; 256]; trie.len()];
+        let mut fail = vec![0usize; trie.len()];
+        let mut out = vec![Vec::new(); trie.len()];
+        let mut queue = VecDeque::from([0usize]);
+        while let Some(state) = queue.pop_front() {
+            if state != 0 {
+                next[state] = next[fail[state]];
+            }
+            for &(b, child) in &trie[state] {
+                let child = child as usize;
+                fail[child] = if state == 0 {
+                    0
+                } else {
+                    next[fail[state]][b as usize] as usize
+                };
+                out[child] = own[child]
+                    .into_iter()
+                    .chain(out[fail[child]].clone())
+                    .collect();
+                next[state][b as usize] = child as u32;
+                queue.push_back(child);
+            }
+        }
+        Automaton {
+            next,
+            out,
+            fold: unicode,
+        }
+    }
+
+    /// Every digit on `line`, in the order the automaton finds them: by end
+    /// position, and longest first among those ending together. Spelled
+    /// digits may overlap, so "twone" yields both 2 and 1.
+    pub fn scan<'a>(&'a self, line: &'a str) -> impl Iterator<Item = Digit<'a>> {
+        let bytes = match self.fold {
+            true => line.chars().map(fold).collect::<String>().into_bytes(),
+            false => line.as_bytes().to_vec(),
+        };
+        let mut state = 0;
+        (0..bytes.len()).flat_map(move |i| {
+            let b = bytes[i];
+            state = self.next[state][b as usize] as usize;
+            self.out[state].iter().map(move |hit| {
+                let pos = i + 1 - hit.len;
+                Digit {
+                    pos,
+                    value: hit.value,
+                    kind: hit.kind,
+                    text: &line[pos..=i],
+                    correction: None,
+                }
+            })
+        })
+    }
+
+    pub fn calibrator(&self) -> Calibrator<'_> {
+        Calibrator {
+            automaton: self,
+            state: 0,
+            pos: 0,
+            pending: ([0; 4], 0),
+            first: None,
+            last: None,
+        }
+    }
+}
+
+/// Streams one line at a time through an [`Automaton`], remembering only the
+/// first and last digit seen so far, so no line needs to be kept around.
+pub struct Calibrator<'a> {
+    automaton: &'a Automaton,
+    state: usize,
+    pos: usize,
+    /// The bytes of a character not yet complete, when case-folding.
+    pending: ([u8; 4], usize),
+    first: Option<(usize, Hit)>,
+    last: Option<(usize, Hit)>,
+}
+
+impl Calibrator<'_> {
+    pub fn feed(&mut self, b: u8) {
+        if !self.automaton.fold {
+            return self.step(b);
+        }
+        let (mut buf, mut len) = self.pending;
+        buf[len] = b;
+        len += 1;
+        let want = match buf[0] {
+            0xf0.. => 4,
+            0xe0.. => 3,
+            0xc0.. => 2,
+            _ => 1,
+        };
+        if len < want {
+            self.pending = (buf, len);
+            return;
+        }
+        self.pending = ([0; 4], 0);
+        match std::str::from_utf8(&buf[..len])
+            .ok()
+            .and_then(|c| c.chars().next())
+        {
+            Some(c) => fold(c)
+                .encode_utf8(&mut [0; 4])
+                .bytes()
+                .for_each(|b| self.step(b)),
+            None => buf[..len].iter().for_each(|&b| self.step(b)),
+        }
+    }
+
+    fn step(&mut self, b: u8) {
+        let automaton = self.automaton;
+        self.state = automaton.next[self.state][b as usize] as usize;
+        self.pos += 1;
+        for &hit in &automaton.out[self.state] {
+            let pos = self.pos - hit.len;
+            if replaces(&self.first, pos, hit.len, |a, b| a > b) {
+                self.first = Some((pos, hit));
+            }
+            if replaces(&self.last, pos, hit.len, |a, b| a < b) {
+                self.last = Some((pos, hit));
+            }
+        }
+    }
+
+    /// The values of the first and last digit of the line fed so far, if it
+    /// had any, resetting for the next line.
+    pub fn end_line(&mut self) -> Option<(u32, u32)> {
+        let first = self.first.take();
+        let last = self.last.take();
+        self.state = 0;
+        self.pos = 0;
+        self.pending = ([0; 4], 0);
+        Some((first?.1.value, last?.1.value))
+    }
+}
+
+/// Whether a match at `pos` of `len` bytes should replace `best`: it is
+/// further along in the `beyond` sense, or at the same place but longer.
+fn replaces(
+    best: &Option<(usize, Hit)>,
+    pos: usize,
+    len: usize,
+    beyond: fn(usize, usize) -> bool,
+) -> bool {
+    match best {
+        None => true,
+        Some((p, hit)) => beyond(*p, pos) || (*p == pos && len > hit.len),
+    }
+}
+
+/// The first and last of `digits`: the leftmost and the rightmost. Of
+/// several that start at the same place, the one needing the fewest
+/// corrections wins, and then the longest.
+pub fn first_last<'a>(
+    mut digits: impl Iterator<Item = Digit<'a>>,
+) -> Option<(Digit<'a>, Digit<'a>)> {
+    let d = digits.next()?;
+    Some(digits.fold((d, d), |(first, last), d| {
+        let better =
+            |e: &Digit| d.pos == e.pos && (d.edits(), e.text.len()) < (e.edits(), d.text.len());
+        (
+            if d.pos < first.pos || better(&first) {
+                d
+            } else {
+                first
+            },
+            if d.pos > last.pos || better(&last) {
+                d
+            } else {
+                last
+            },
+        )
+    }))
+}
+
+/// The number of single-character insertions, deletions, substitutions and
+/// swaps of neighbours that turn `a` into `b` (optimal string alignment).
+fn distance(a: &[char], b: &[char]) -> usize {
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    d[0] = (0..=b.len()).collect();
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// Misspelled words on `line`: text within `max` edits of a word in
+/// `lexicon`, but not overlapping any exact word in `exact`.
+pub fn misspellings<'a>(
+    line: &'a str,
+    exact: &[Digit<'a>],
+    lexicon: &'a Lexicon,
+    max: usize,
+    unicode: bool,
+) -> Vec<Digit<'a>> {
+    let chars: Vec<(usize, char)> = line
+        .char_indices()
+        .map(|(i, c)| (i, if unicode { fold(c) } else { c }))
+        .collect();
+    let text: Vec<char> = chars.iter().map(|&(_, c)| c).collect();
+    let words: Vec<(&str, Vec<char>, u32)> = lexicon
+        .words()
+        .map(|(w, v)| (w, w.chars().collect(), v))
+        .collect();
+    let byte = |i: usize| chars.get(i).map_or(line.len(), |&(b, _)| b);
+    let covered = |start, end| {
+        exact
+            .iter()
+            .any(|d| d.kind == Kind::Spelled && d.pos < end && start < d.pos + d.text.len())
+    };
+
+    let mut found = Vec::new();
+    for start in 0..text.len() {
+        for (word, spelling, value) in &words {
+            let shortest = spelling.len().saturating_sub(max).max(1);
+            let longest = (spelling.len() + max).min(text.len() - start);
+            for len in shortest..=longest {
+                let edits = distance(&text[start..start + len], spelling);
+                let (pos, end) = (byte(start), byte(start + len));
+                if (1..=max).contains(&edits) && !covered(pos, end) {
+                    found.push(Digit {
+                        pos,
+                        value: *value,
+                        kind: Kind::Spelled,
+                        text: &line[pos..end],
+                        correction: Some(Correction { word, edits }),
+                    });
+                }
+            }
+        }
+    }
+    found
+}
+
+/// The calibration value: the first digit's value followed by the last's,
+/// i.e. `10 * first + last` for single digits. `None` if it overflows.
+pub fn combine(first: u32, last: u32) -> Option<u32> {
+    let shift = last.checked_ilog10().unwrap_or(0) + 1;
+    first.checked_mul(10u32.pow(shift))?.checked_add(last)
+}
+
+/// The calibration value made of the first and last of `digits`.
+pub fn calibration<'a>(digits: impl Iterator<Item = Digit<'a>>) -> Option<u32> {
+    first_last(digits).and_then(|(first, last)| combine(first.value, last.value))
+}
+
+fn no_digit(i: usize) -> Error {
+    Error::invalid(format!("line {} has no digit", i + 1))
+}
+
+/// Sums the calibration values of `input` in one pass over its bytes, so it
+/// can be fed straight from a reader. Lines end in `\n`, as with `str::lines`.
+pub fn total(
+    automaton: &Automaton,
+    digitless: Digitless,
+    input: impl IntoIterator<Item = u8>,
+) -> Result<u32> {
+    let mut totals = Totals::new(&[automaton], digitless);
+    input.into_iter().try_for_each(|b| totals.feed(b))?;
+    Ok(totals.finish()?[0])
+}
+
+/// Running calibration sums under several automata at once, fed one byte at
+/// a time, so one pass over a reader answers every part.
+pub struct Totals<'a> {
+    calibrators: Vec<Calibrator<'a>>,
+    digitless: Digitless,
+    sums: Vec<u32>,
+    line: usize,
+    /// Whether the current line has any bytes yet.
+    pending: bool,
+}
+
+impl<'a> Totals<'a> {
+    pub fn new(automata: &[&'a Automaton], digitless: Digitless) -> Totals<'a> {
+        Totals {
+            calibrators: automata.iter().map(|a| a.calibrator()).collect(),
+            digitless,
+            sums: vec![0; automata.len()],
+            line: 0,
+            pending: false,
+        }
+    }
+
+    pub fn feed(&mut self, b: u8) -> Result<()> {
+        if b != b'\n' {
+            self.calibrators.iter_mut().for_each(|c| c.feed(b));
+            self.pending = true;
+            return Ok(());
+        }
+        self.end_line()?;
+        self.line += 1;
+        self.pending = false;
+        Ok(())
+    }
+
+    fn end_line(&mut self) -> Result<()> {
+        let line = self.line;
+        for (calibrator, sum) in self.calibrators.iter_mut().zip(&mut self.sums) {
+            let n = match calibrator.end_line() {
+                Some((first, last)) => combine(first, last),
+                None if self.digitless == Digitless::Error => return Err(no_digit(line)),
+                None => Some(0),
+            };
+            *sum = n
+                .and_then(|n| sum.checked_add(n))
+                .ok_or_else(|| Error::overflow(format!("calibration sum at line {}", line + 1)))?;
+        }
+        Ok(())
+    }
+
+    /// The sums, one per automaton, once the last line is in.
+    pub fn finish(mut self) -> Result<Vec<u32>> {
+        if self.pending {
+            self.end_line()?;
+        }
+        Ok(self.sums)
+    }
+}
+
+impl Day01 {
+    /// Every digit on `line` that counts towards `part`, misspelled words
+    /// included when fuzzy matching is on.
+    fn scan<'a>(&'a self, line: &'a str, part: Part) -> Vec<Digit<'a>> {
+        match part {
+            Part::One => self.digits.scan(line).collect(),
+            Part::Two => {
+                let mut digits: Vec<_> = self.words.scan(line).collect();
+                if self.fuzzy > 0 {
+                    let fuzzy =
+                        misspellings(line, &digits, &self.lexicon, self.fuzzy, self.unicode);
+                    digits.extend(fuzzy);
+                }
+                digits
+            }
+        }
+    }
+
+    /// Answers `parts` from `input` one line at a time, reusing one buffer.
+    fn stream_lines(&self, input: &mut dyn BufRead, parts: &[Part]) -> Result<Vec<u32>> {
+        let mut sums = vec![0u32; parts.len()];
+        let mut buf = Vec::new();
+        for i in 0.. {
+            buf.clear();
+            if input.read_until(b'\n', &mut buf)? == 0 {
+                break;
+            }
+            let line = std::str::from_utf8(&buf)
+                .map_err(|_| Error::invalid(format!("line {} is not valid UTF-8", i + 1)))?;
+            let line = line.strip_suffix('\n').unwrap_or(line);
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            for (sum, &part) in sums.iter_mut().zip(parts) {
+                let value = self.calibrate(i, line, part)?.map_or(0, |(_, _, n)| n);
+                *sum = sum
+                    .checked_add(value)
+                    .ok_or_else(|| Error::overflow(format!("calibration sum at line {}", i + 1)))?;
+            }
+        }
+        Ok(sums)
+    }
+
+    /// The first and last digit of line `i` and its calibration value, or
+    /// `None` for a line without digits that the policy lets through.
+    fn calibrate<'a>(
+        &'a self,
+        i: usize,
+        line: &'a str,
+        part: Part,
+    ) -> Result<Option<(Digit<'a>, Digit<'a>, u32)>> {
+        match first_last(self.scan(line, part).into_iter()) {
+            Some((first, last)) => {
+                let value = combine(first.value, last.value).ok_or_else(|| {
+                    Error::overflow(format!("calibration value at line {}", i + 1))
+                })?;
+                Ok(Some((first, last, value)))
+            }
+            None if self.digitless == Digitless::Error => Err(no_digit(i)),
+            None => Ok(None),
+        }
+    }
+}
+
+impl Solution for Day01 {
+    type Model = String;
+    type Answer = u32;
+
+    fn configure(opts: &mut Options) -> Result<Day01> {
+        let mut lexicon = match opts.value("lexicon")? {
+            Some(path) => Lexicon::load(Path::new(&path))?,
+            None => Lexicon::default(),
+        };
+        for words in opts.values("words")? {
+            lexicon = lexicon.extend(&Lexicon::parse(&words)?)?;
+        }
+        let unicode = opts.flag("unicode")?;
+        let digitless = opts.parsed("digitless")?.unwrap_or_default();
+        let fuzzy = opts.parsed("fuzzy")?.unwrap_or(0);
+        // With as many edits as a word has letters, any text would match it.
+        if let Some((word, _)) = lexicon.words().find(|(w, _)| fuzzy >= w.chars().count()) {
+            return Err(Error::usage(format!(
+                "--fuzzy {fuzzy} would let anything match `{word}`; use fewer edits than its length"
+            )));
+        }
+        Ok(Day01 {
+            digitless,
+            fuzzy,
+            ..Day01::new(&lexicon, unicode)?
+        })
+    }
+
+    fn parse(&self, input: &str) -> Result<String> {
+        Ok(input.to_string())
+    }
+
+    /// Without fuzzy matching, every part's automaton runs over the reader
+    /// in the same pass, keeping only each line's first and last digit.
+    /// Fuzzy matching needs whole lines, so then one line at a time is read.
+    fn stream(&self, input: &mut dyn BufRead, parts: &[Part]) -> Result<Vec<u32>> {
+        if self.fuzzy > 0 {
+            return self.stream_lines(input, parts);
+        }
+        let automata: Vec<&Automaton> = parts
+            .iter()
+            .map(|part| match part {
+                Part::One => &self.digits,
+                Part::Two => &self.words,
+            })
+            .collect();
+        let mut totals = Totals::new(&automata, self.digitless);
+        loop {
+            let buf = input.fill_buf()?;
+            if buf.is_empty() {
+                break;
+            }
+            buf.iter().try_for_each(|&b| totals.feed(b))?;
+            let len = buf.len();
+            input.consume(len);
+        }
+        totals.finish()
+    }
+
+    fn part1(&self, input: &String) -> Result<u32> {
+        total(&self.digits, self.digitless, input.bytes())
+    }
+
+    fn part2(&self, input: &String) -> Result<u32> {
+        if self.fuzzy == 0 {
+            return total(&self.words, self.digitless, input.bytes());
+        }
+        input.lines().enumerate().try_fold(0u32, |sum, (i, line)| {
+            let value = self.calibrate(i, line, Part::Two)?.map_or(0, |(_, _, n)| n);
+            sum.checked_add(value)
+                .ok_or_else(|| Error::overflow(format!("calibration sum at line {}", i + 1)))
+        })
+    }
+
+    /// Lists each line's first and last digit, as matched, its value, and
+    /// any misspelled words taken for digits.
+    fn report(&self, input: &String, part: Part, out: &mut dyn Write) -> Result<()> {
+        let show = |d: &Digit| match d.kind {
+            Kind::Numeric => d.text.to_string(),
+            Kind::Spelled => format!("{}={}", d.text, d.value),
+        };
+
+        writeln!(
+            out,
+            "{:>5}  {:<14} {:<14} {:>10}  corrections",
+            "line", "first", "last", "value"
+        )?;
+        let mut sum = 0u32;
+        for (i, line) in input.lines().enumerate() {
+            let Some((first, last, value)) = self.calibrate(i, line, part)? else {
+                let value = match self.digitless {
+                    Digitless::Skip => "skipped",
+                    _ => "0",
+                };
+                writeln!(out, "{:>5}  {:<14} {:<14} {value:>10}", i + 1, "-", "-")?;
+                continue;
+            };
+            sum = sum
+                .checked_add(value)
+                .ok_or_else(|| Error::overflow(format!("calibration sum at line {}", i + 1)))?;
+            let mut corrections: Vec<String> = [first, last]
+                .iter()
+                .filter_map(|d| Some(format!("{} -> {}", d.text, d.correction?.word)))
+                .collect();
+            corrections.dedup();
+            let row = format!(
+                "{:>5}  {:<14} {:<14} {value:>10}  {}",
+                i + 1,
+                show(&first),
+                show(&last),
+                corrections.join(", ")
+            );
+            writeln!(out, "{}", row.trim_end())?;
+        }
+        writeln!(out, "{:>5}  {:<14} {:<14} {sum:>10}", "total", "", "")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE1: &str = "1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet
+";
+
+    const SAMPLE2: &str = "two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen
+";
+
+    fn part1(input: &str) -> Result<u32> {
+        Day01::default().part1(&Day01::default().parse(input)?)
+    }
+
+    fn part2(input: &str) -> Result<u32> {
+        Day01::default().part2(&Day01::default().parse(input)?)
+    }
+
+    #[test]
+    fn sample() {
+        assert_eq!(part1(SAMPLE1).unwrap(), 142);
+        assert_eq!(part2(SAMPLE2).unwrap(), 281);
+    }
+
+    #[test]
+    fn overlapping_words() {
+        assert_eq!(part2("eightwo").unwrap(), 82);
+        assert_eq!(part2("twone").unwrap(), 21);
+        assert_eq!(part2("oneighthree").unwrap(), 13);
+    }
+
+    #[test]
+    fn single_digit_counts_twice() {
+        assert_eq!(part1("treb7uchet").unwrap(), 77);
+        assert_eq!(part2("seven").unwrap(), 77);
+    }
+
+    #[test]
+    fn scan_reports_overlapping_matches() {
+        let automaton = Automaton::new(Some(&Lexicon::default()), false);
+        let digits: Vec<_> = automaton.scan("x2twone").collect();
+        assert_eq!(
+            digits,
+            [
+                Digit {
+                    pos: 1,
+                    value: 2,
+                    kind: Kind::Numeric,
+                    text: "2",
+                    correction: None,
+                },
+                Digit {
+                    pos: 2,
+                    value: 2,
+                    kind: Kind::Spelled,
+                    text: "two",
+                    correction: None,
+                },
+                Digit {
+                    pos: 4,
+                    value: 1,
+                    kind: Kind::Spelled,
+                    text: "one",
+                    correction: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn line_without_digit() {
+        assert!(matches!(part1("1a\nabc"), Err(Error::Invalid(_))));
+        assert!(matches!(part2("1a\nabc"), Err(Error::Invalid(_))));
+    }
+
+    fn solve(lexicon: &Lexicon, input: &str) -> Result<u32> {
+        let day = Day01::new(lexicon, false)?;
+        day.part2(&day.parse(input)?)
+    }
+
+    #[test]
+    fn spanish_lexicon() {
+        let lexicon = Lexicon::parse("uno=1, dos=2, tres=3\ncuatro=4").unwrap();
+        assert_eq!(solve(&lexicon, "xdosuno\ncuatrotres").unwrap(), 21 + 43);
+        assert!(solve(&lexicon, "one").is_err());
+    }
+
+    #[test]
+    fn zero_and_teens() {
+        let extra = Lexicon::parse("zero=0\nseventeen=17").unwrap();
+        let lexicon = Lexicon::default().extend(&extra).unwrap();
+        assert_eq!(solve(&lexicon, "zeroseven").unwrap(), 7);
+        assert_eq!(solve(&lexicon, "seventeentwo").unwrap(), 172);
+        assert_eq!(solve(&lexicon, "oneseventeen").unwrap(), 117);
+    }
+
+    #[test]
+    fn rejects_ambiguous_lexicon() {
+        assert!(Lexicon::parse("uno=1\nuno=2").is_err());
+        assert!(Lexicon::parse("uno=1\nuno=1").is_ok());
+        assert!(Lexicon::parse("n1ne=9").is_err());
+        assert!(Lexicon::parse("=9").is_err());
+        let Err(Error::Parse { line, col, .. }) = Lexicon::parse("uno=1, dos") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((line, col), (1, 8));
+        let Err(Error::Parse { col, .. }) = Lexicon::parse("a=1,bb,c=3") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(col, 5);
+        let Err(Error::Parse { col, .. }) = Lexicon::parse("uno, dos=2") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(col, 1);
+    }
+
+    #[test]
+    fn streams_lines_in_one_pass() {
+        let automaton = Automaton::new(Some(&Lexicon::default()), false);
+        let input = "two1nine\r\neightwothree\nsix";
+        assert_eq!(
+            total(&automaton, Digitless::Error, input.bytes()).unwrap(),
+            29 + 83 + 66
+        );
+        let mut calibrator = automaton.calibrator();
+        "xtwone".bytes().for_each(|b| calibrator.feed(b));
+        assert_eq!(calibrator.end_line(), Some((2, 1)));
+        assert_eq!(calibrator.end_line(), None);
+    }
+
+    #[test]
+    fn scan_agrees_with_calibrator() {
+        let extra = Lexicon::parse("seventeen=17, teen=10, en=5").unwrap();
+        let lexicon = Lexicon::default().extend(&extra).unwrap();
+        let automaton = Automaton::new(Some(&lexicon), false);
+        for line in ["seventeen", "xseventeenx", "9seventeen", "teenseven"] {
+            let mut calibrator = automaton.calibrator();
+            line.bytes().for_each(|b| calibrator.feed(b));
+            let (first, last) = first_last(automaton.scan(line)).unwrap();
+            assert_eq!(
+                calibrator.end_line(),
+                Some((first.value, last.value)),
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn digitless_policy() {
+        let mut day = Day01::default();
+        assert!(matches!(part2("1a\nabc\n"), Err(Error::Invalid(_))));
+        day.digitless = "skip".parse().unwrap();
+        assert_eq!(day.part2(&"1a\nabc\ntwo".to_string()).unwrap(), 11 + 22);
+        day.digitless = "zero".parse().unwrap();
+        assert_eq!(day.part1(&"1a\nabc\ntwo".to_string()).unwrap(), 11);
+        assert!("none".parse::<Digitless>().is_err());
+    }
+
+    #[test]
+    fn report_lists_each_line() {
+        let day = Day01 {
+            digitless: Digitless::Skip,
+            ..Day01::default()
+        };
+        let mut out = Vec::new();
+        day.report(&"x2twone\nabc".to_string(), Part::Two, &mut out)
+            .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<_> = out
+            .lines()
+            .map(str::split_whitespace)
+            .map(Iterator::collect::<Vec<_>>)
+            .collect();
+        assert_eq!(lines[1], ["1", "2", "one=1", "21"]);
+        assert_eq!(lines[2], ["2", "-", "-", "skipped"]);
+        assert_eq!(lines[3], ["total", "21"]);
+    }
+
+    #[test]
+    fn unicode_digits_and_case() {
+        let day = Day01::new(&Lexicon::default(), true).unwrap();
+        let input = "\u{ff11}abc\u{663}\nSevenTWO\nx\u{966}NINE".to_string();
+        assert!(matches!(day.part1(&input), Err(Error::Invalid(_))));
+        assert_eq!(day.part2(&input).unwrap(), 13 + 72 + 9);
+        assert_eq!(day.part1(&"\u{ff11}abc\u{663}".to_string()).unwrap(), 13);
+        assert!(Day01::default().part1(&"\u{ff11}x".to_string()).is_err());
+
+        let digits = day.words.scan("ÉÉ\u{1d7d9}Seven");
+        let texts: Vec<_> = digits.map(|d| (d.pos, d.text, d.value)).collect();
+        assert_eq!(texts, [(4, "\u{1d7d9}", 1), (8, "Seven", 7)]);
+    }
+
+    #[test]
+    fn folding_keeps_lexicon_unambiguous() {
+        let lexicon = Lexicon::parse("Uno=1, uno=2").unwrap();
+        assert!(Day01::new(&lexicon, false).is_ok());
+        assert!(Day01::new(&lexicon, true).is_err());
+        let lexicon = Lexicon::parse("ÜBER=4").unwrap();
+        let day = Day01::new(&lexicon, true).unwrap();
+        assert_eq!(day.part2(&"xüberx".to_string()).unwrap(), 44);
+    }
+
+    #[test]
+    fn fuzzy_words() {
+        let day = Day01 {
+            fuzzy: 1,
+            ..Day01::default()
+        };
+        let input = "sevn2\n3eihgt\nxonx4\nfour\ntqo9".to_string();
+        assert_eq!(day.part2(&input).unwrap(), 72 + 38 + 14 + 44 + 29);
+        assert!(Day01::default().part2(&"sevn".to_string()).is_err());
+
+        let digits = day.scan("eihgtxtwo", Part::Two);
+        let (first, last) = first_last(digits.into_iter()).unwrap();
+        assert_eq!((first.text, first.value), ("eihgt", 8));
+        assert_eq!(first.correction.map(|c| c.word), Some("eight"));
+        assert_eq!((last.text, last.correction), ("two", None));
+    }
+
+    #[test]
+    fn streams_from_a_reader() {
+        let input = "two1nine\r\n4nineeightseven2\nzoneight234\n";
+        let day = Day01::default();
+        let parts = [Part::One, Part::Two];
+        assert_eq!(
+            day.stream(&mut input.as_bytes(), &parts).unwrap(),
+            [11 + 42 + 24, 29 + 42 + 14]
+        );
+        assert!(day.stream(&mut "ab\ncd".as_bytes(), &parts).is_err());
+        assert_eq!(
+            day.stream(&mut "a1b\n2".as_bytes(), &[Part::Two]).unwrap(),
+            [33]
+        );
+
+        let fuzzy = Day01 {
+            fuzzy: 1,
+            ..Day01::default()
+        };
+        let input = "sevn2\r\n3eihgt";
+        assert_eq!(
+            fuzzy.stream(&mut input.as_bytes(), &[Part::Two]).unwrap(),
+            [72 + 38]
+        );
+    }
+
+    #[test]
+    fn fuzzy_must_leave_a_letter() {
+        let configure = |edits: &str| {
+            let mut opts = Options::default();
+            opts.push("fuzzy", Some(edits.to_string()));
+            Day01::configure(&mut opts).map(|day| day.fuzzy)
+        };
+        assert_eq!(configure("2").unwrap(), 2);
+        assert!(matches!(configure("3"), Err(Error::Usage(_))));
+    }
+
+    #[test]
+    fn edit_distance() {
+        let d = |a: &str, b: &str| {
+            distance(
+                &a.chars().collect::<Vec<_>>(),
+                &b.chars().collect::<Vec<_>>(),
+            )
+        };
+        assert_eq!(d("seven", "seven"), 0);
+        assert_eq!(d("sevn", "seven"), 1);
+        assert_eq!(d("eihgt", "eight"), 1);
+        assert_eq!(d("nnie", "nine"), 1);
+        assert_eq!(d("", "four"), 4);
+    }
+}
@@ -0,0 +1,904 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+
+use nom::{
+    bytes::complete::{tag, take_while1},
+    character::complete::{char, digit1, satisfy, space0},
+    combinator::{cut, map_res, not, opt},
+    error::{context, VerboseError, VerboseErrorKind},
+    multi::separated_list1,
+    sequence::{pair, separated_pair, terminated, tuple},
+};
+
+use crate::parse::{self, PResult};
+use crate::{Error, Options, Part, Result, Solution};
+
+pub mod query;
+
+use query::Query;
+
+/// A handful of cubes by color: one reveal from a game, or what a bag holds.
+/// Any word names a color; colors not mentioned count as none.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct Reveal(BTreeMap<String, u32>);
+
+impl Reveal {
+    pub fn get(&self, color: &str) -> u32 {
+        self.0.get(color).copied().unwrap_or(0)
+    }
+
+    pub fn colors(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(String::as_str)
+    }
+
+    /// Whether these cubes could all have come out of `bag`.
+    pub fn fits(&self, bag: &Reveal) -> bool {
+        self.0.iter().all(|(color, &n)| n <= bag.get(color))
+    }
+
+    /// The most cubes of each color in either.
+    pub fn max(mut self, other: &Reveal) -> Reveal {
+        for (color, &n) in &other.0 {
+            let count = self.0.entry(color.clone()).or_default();
+            *count = (*count).max(n);
+        }
+        self
+    }
+
+    /// The numbers of cubes of each of `colors` multiplied together.
+    pub fn power<'a>(&self, colors: impl IntoIterator<Item = &'a str>) -> Option<u32> {
+        colors
+            .into_iter()
+            .try_fold(1u32, |acc, color| acc.checked_mul(self.get(color)))
+    }
+}
+
+impl<S: Into<String>, const N: usize> From<[(S, u32); N]> for Reveal {
+    fn from(cubes: [(S, u32); N]) -> Reveal {
+        Reveal(cubes.into_iter().map(|(c, n)| (c.into(), n)).collect())
+    }
+}
+
+/// The bag from the puzzle: 12 red cubes, 13 green cubes, and 14 blue cubes.
+pub fn bag() -> Reveal {
+    Reveal::from([("red", 12), ("green", 13), ("blue", 14)])
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Game {
+    pub id: u32,
+    pub reveals: Vec<Reveal>,
+    /// Colors some reveal named more than once, merged by [`Duplicates`].
+    pub repeated: Vec<String>,
+}
+
+impl Game {
+    pub fn possible(&self, bag: &Reveal) -> bool {
+        self.reveals.iter().all(|reveal| reveal.fits(bag))
+    }
+
+    /// The fewest cubes of each color the bag could have held.
+    pub fn fewest(&self) -> Reveal {
+        self.reveals.iter().fold(Reveal::default(), Reveal::max)
+    }
+}
+
+/// Every color named anywhere in `games`.
+pub fn palette(games: &[Game]) -> BTreeSet<&str> {
+    games
+        .iter()
+        .flat_map(|game| &game.reveals)
+        .flat_map(Reveal::colors)
+        .collect()
+}
+
+fn parse_amount(input: &str) -> PResult<'_, u32> {
+    context("a number", map_res(digit1, str::parse))(input)
+}
+
+/// A color name: letters from any script, and hyphens, as in `light-blue`.
+fn parse_color(input: &str) -> PResult<'_, &str> {
+    take_while1(|c: char| c.is_alphabetic() || c == '-')(input)
+}
+
+const EXPECTED_CUBES: &str = "a cube count and color, like `3 blue`";
+
+/// Any failure is reported at the start of the item, as a whole.
+fn parse_cubes(input: &str) -> PResult<'_, (u32, &str)> {
+    let color = terminated(parse_color, not(satisfy(char::is_alphanumeric)));
+    separated_pair(parse_amount, tag(" "), color)(input).map_err(|e| {
+        e.map(|_| VerboseError {
+            errors: vec![(input, VerboseErrorKind::Context(EXPECTED_CUBES))],
+        })
+    })
+}
+
+/// What to make of a reveal that names the same color twice, as in
+/// `3 red, 2 red`. Either way the game gets a warning.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum Duplicates {
+    /// Both handfuls were shown: 5 red.
+    #[default]
+    Sum,
+    /// The larger count restates the smaller: 3 red.
+    Max,
+    /// The input is malformed.
+    Error,
+}
+
+impl FromStr for Duplicates {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Duplicates> {
+        match s {
+            "sum" => Ok(Duplicates::Sum),
+            "max" => Ok(Duplicates::Max),
+            "error" => Ok(Duplicates::Error),
+            _ => Err(Error::usage(format!(
+                "--duplicates expects sum, max or error, not `{s}`"
+            ))),
+        }
+    }
+}
+
+impl Duplicates {
+    /// Builds the game on line `line` from each reveal's cube counts.
+    fn game(self, line: usize, id: u32, reveals: Vec<Vec<(u32, &str)>>) -> Result<Game> {
+        let mut repeated: Vec<String> = Vec::new();
+        let mut merged = Vec::with_capacity(reveals.len());
+        for cubes in reveals {
+            let mut reveal = Reveal::default();
+            for (n, color) in cubes {
+                let Some(count) = reveal.0.get_mut(color) else {
+                    reveal.0.insert(color.to_string(), n);
+                    continue;
+                };
+                *count = match self {
+                    Duplicates::Sum => count
+                        .checked_add(n)
+                        .ok_or_else(|| Error::overflow(format!("{color} cubes in game {id}")))?,
+                    Duplicates::Max => (*count).max(n),
+                    Duplicates::Error => {
+                        return Err(Error::invalid(format!(
+                            "line {line}: game {id} names {color} twice in one reveal"
+                        )))
+                    }
+                };
+                if !repeated.iter().any(|c| c == color) {
+                    repeated.push(color.to_string());
+                }
+            }
+            merged.push(reveal);
+        }
+        Ok(Game {
+            id,
+            reveals: merged,
+            repeated,
+        })
+    }
+}
+
+fn parse_reveal(input: &str) -> PResult<'_, Vec<(u32, &str)>> {
+    separated_list1(tag(", "), cut(parse_cubes))(input)
+}
+
+type BagSpec<'a> = (Option<&'a str>, Vec<(&'a str, u32)>);
+
+/// A bag as `[name: ]color=count, ...`.
+fn parse_bag(input: &str) -> PResult<'_, BagSpec<'_>> {
+    let name = take_while1(|c: char| c.is_alphanumeric() || c == '-' || c == '_');
+    let cubes = separated_pair(
+        context("a color", parse_color),
+        context("`=` after color", char('=')),
+        parse_amount,
+    );
+    pair(
+        opt(terminated(name, pair(char(':'), space0))),
+        separated_list1(pair(char(','), space0), cubes),
+    )(input)
+}
+
+/// Candidate bags, one per line, as in `--bag`. Blank lines and lines
+/// starting with `#` are ignored; unnamed bags are numbered from `first`.
+pub fn parse_bags(text: &str, first: usize) -> Result<Vec<(String, Reveal)>> {
+    let mut bags = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let (name, cubes) = parse::line(i + 1, line.trim(), parse_bag)?;
+        let name = match name {
+            Some(name) => name.to_string(),
+            None => format!("bag {}", first + bags.len()),
+        };
+        let mut bag = Reveal::default();
+        for (color, n) in cubes {
+            if bag.0.insert(color.to_string(), n).is_some() {
+                return Err(Error::invalid(format!(
+                    "bag `{name}` lists {color} more than once"
+                )));
+            }
+        }
+        bags.push((name, bag));
+    }
+    Ok(bags)
+}
+
+fn parse_header(input: &str) -> PResult<'_, u32> {
+    let (input, (_, game, _)) = tuple((
+        context("`Game `", tag("Game ")),
+        context("game id", parse_amount),
+        context("`: ` after game id", tag(": ")),
+    ))(input)?;
+    Ok((input, game))
+}
+
+type GameSpec<'a> = (u32, Vec<Vec<(u32, &'a str)>>);
+
+fn parse_game(input: &str) -> PResult<'_, GameSpec<'_>> {
+    pair(parse_header, separated_list1(tag("; "), cut(parse_reveal)))(input)
+}
+
+/// How `--infer` turns reveals into an estimate of what a bag holds.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Estimate {
+    /// The bag under which the reveals are most likely.
+    MaxLikelihood,
+    /// The expected bag, with every bag of up to `--max-cubes` cubes of
+    /// each color equally likely beforehand.
+    PosteriorMean,
+}
+
+impl FromStr for Estimate {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Estimate> {
+        match s {
+            "mle" => Ok(Estimate::MaxLikelihood),
+            "mean" => Ok(Estimate::PosteriorMean),
+            _ => Err(Error::usage(format!(
+                "--infer expects mle or mean, not `{s}`"
+            ))),
+        }
+    }
+}
+
+/// An estimated bag, as a count per color, and the natural log of the
+/// likelihood behind it: of the reveals under the bag for
+/// [`Estimate::MaxLikelihood`], and averaged over the prior for
+/// [`Estimate::PosteriorMean`]. A color is `capped` when `max_cubes`, not
+/// the reveals, settles its estimate: one more cube would make the reveals
+/// likelier still, or the posterior piles up at the cap.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Inference {
+    pub bag: Vec<f64>,
+    pub ln_likelihood: f64,
+    pub capped: Vec<bool>,
+}
+
+/// The most bags `infer` will weigh up before giving up.
+const MAX_BAGS: u64 = 10_000_000;
+
+/// The most cubes a bag may hold in all, which sizes the table of
+/// factorials `infer` works from.
+const MAX_TOTAL: u64 = 1_000_000;
+
+/// Estimates the bag behind every reveal of `games` together, counting the
+/// cubes of each of `colors`. Each reveal is taken to be one handful drawn
+/// without replacement, and the cubes go back in the bag before the next,
+/// so a reveal's likelihood is multivariate hypergeometric. Bags hold at
+/// most `max_cubes` of any color, and every such bag is tried.
+pub fn infer(
+    games: &[&Game],
+    colors: &[&str],
+    max_cubes: u32,
+    estimate: Estimate,
+) -> Result<Inference> {
+    let reveals: Vec<Vec<u32>> = games
+        .iter()
+        .flat_map(|game| &game.reveals)
+        .map(|reveal| colors.iter().map(|color| reveal.get(color)).collect())
+        .collect();
+    let least: Vec<u32> = (0..colors.len())
+        .map(|c| reveals.iter().map(|r| r[c]).max().unwrap_or(0))
+        .collect();
+    if let Some(c) = least.iter().position(|&n| n > max_cubes) {
+        return Err(Error::invalid(format!(
+            "a bag needs {} {} cubes, more than --max-cubes {max_cubes}",
+            least[c], colors[c]
+        )));
+    }
+    // Bags one cube past the cap are weighed too, to see if it binds.
+    let most = (u64::from(max_cubes) + 1) * colors.len() as u64;
+    if most > MAX_TOTAL {
+        return Err(Error::usage(format!(
+            "--max-cubes {max_cubes} allows bags of over {MAX_TOTAL} cubes, too many to weigh up"
+        )));
+    }
+    least
+        .iter()
+        .try_fold(1u64, |acc, &n| {
+            acc.checked_mul(u64::from(max_cubes - n) + 1)
+        })
+        .filter(|&bags| bags <= MAX_BAGS)
+        .ok_or_else(|| {
+            Error::invalid(format!(
+                "too many bags to weigh up with {} colors; lower --max-cubes",
+                colors.len()
+            ))
+        })?;
+
+    // ln(n!) for every n a bag can hold in total.
+    let ln_fact: Vec<f64> = (0..=most as usize)
+        .scan(0.0, |acc, n| {
+            *acc += f64::ln(n.max(1) as f64);
+            Some(*acc)
+        })
+        .collect();
+    let ln_choose =
+        |n: u32, k: u32| ln_fact[n as usize] - ln_fact[k as usize] - ln_fact[(n - k) as usize];
+    let ln_likelihood = |bag: &[u32]| {
+        let total = bag.iter().sum();
+        reveals
+            .iter()
+            .map(|reveal| {
+                let drawn = reveal.iter().sum();
+                let ways: f64 = bag.iter().zip(reveal).map(|(&n, &k)| ln_choose(n, k)).sum();
+                ways - ln_choose(total, drawn)
+            })
+            .sum::<f64>()
+    };
+
+    let mut bag = least.clone();
+    let mut best = (f64::NEG_INFINITY, bag.clone());
+    // Likelihoods relative to the first bag, to keep the sums in range.
+    let mut scale = None;
+    let mut evidence = 0.0;
+    let mut mean = vec![0.0; colors.len()];
+    let mut at_cap = vec![0.0; colors.len()];
+    loop {
+        let ln_l = ln_likelihood(&bag);
+        if ln_l > best.0 {
+            best = (ln_l, bag.clone());
+        }
+        let w = f64::exp(ln_l - *scale.get_or_insert(ln_l));
+        evidence += w;
+        for (c, &n) in bag.iter().enumerate() {
+            mean[c] += w * f64::from(n);
+            if n == max_cubes {
+                at_cap[c] += w;
+            }
+        }
+
+        // The next bag, counting up like an odometer.
+        let Some(c) = (0..bag.len()).find(|&c| bag[c] < max_cubes) else {
+            break;
+        };
+        bag[c] += 1;
+        bag[..c].copy_from_slice(&least[..c]);
+    }
+
+    Ok(match estimate {
+        Estimate::MaxLikelihood => {
+            // Past the cap, each color at it alone, and all of them together.
+            let at = |c: usize| best.1[c] == max_cubes;
+            let beyond = |grow: &dyn Fn(usize) -> bool| {
+                let bag: Vec<u32> = (0..colors.len())
+                    .map(|c| best.1[c] + u32::from(grow(c)))
+                    .collect();
+                ln_likelihood(&bag) > best.0
+            };
+            let together = (0..colors.len()).any(at) && beyond(&at);
+            Inference {
+                capped: (0..colors.len())
+                    .map(|c| at(c) && (together || beyond(&|d| d == c)))
+                    .collect(),
+                bag: best.1.into_iter().map(f64::from).collect(),
+                ln_likelihood: best.0,
+            }
+        }
+        Estimate::PosteriorMean => {
+            // The prior spreads over every bag, including those the reveals
+            // rule out, which all contribute nothing.
+            let prior = (f64::from(max_cubes) + 1.0).powi(colors.len() as i32);
+            Inference {
+                bag: mean.into_iter().map(|m| m / evidence).collect(),
+                ln_likelihood: scale.unwrap_or(0.0) + f64::ln(evidence / prior),
+                // At least as much weight at the cap as if the reveals said
+                // nothing about the color beyond its least possible count.
+                capped: (0..colors.len())
+                    .map(|c| at_cap[c] / evidence >= 1.0 / f64::from(max_cubes - least[c] + 1))
+                    .collect(),
+            }
+        }
+    })
+}
+
+/// Part 1's answer: the sum of the ids of possible games, for each bag.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Tally {
+    One(u32),
+    PerBag(Vec<(String, u32)>),
+}
+
+impl Display for Tally {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tally::One(n) => n.fmt(f),
+            Tally::PerBag(sums) => {
+                let sums: Vec<_> = sums.iter().map(|(bag, n)| format!("{bag}: {n}")).collect();
+                f.pad(&sums.join(", "))
+            }
+        }
+    }
+}
+
+pub struct Day02 {
+    bags: Vec<(String, Reveal)>,
+    duplicates: Duplicates,
+    /// Makes the report estimate bags rather than list answers.
+    infer: Option<Estimate>,
+    max_cubes: u32,
+    /// Makes the report list the games matching this instead.
+    query: Option<Query>,
+}
+
+impl Default for Day02 {
+    fn default() -> Day02 {
+        Day02 {
+            bags: vec![("puzzle".to_string(), bag())],
+            duplicates: Duplicates::default(),
+            infer: None,
+            max_cubes: 30,
+            query: None,
+        }
+    }
+}
+
+impl Day02 {
+    fn report_inference(
+        &self,
+        games: &[Game],
+        estimate: Estimate,
+        out: &mut dyn Write,
+    ) -> Result<()> {
+        let colors: Vec<&str> = palette(games).into_iter().collect();
+        // Whether the row had any estimate set by the cap.
+        let row = |out: &mut dyn Write, label: &str, games: &[&Game]| -> Result<bool> {
+            let inference = infer(games, &colors, self.max_cubes, estimate)?;
+            write!(out, "{label:>6}")?;
+            for (n, &capped) in inference.bag.iter().zip(&inference.capped) {
+                let n = match estimate {
+                    Estimate::MaxLikelihood => n.to_string(),
+                    Estimate::PosteriorMean => format!("{n:.2}"),
+                };
+                let mark = if capped { "+" } else { " " };
+                write!(out, "  {n:>8}{mark}")?;
+            }
+            writeln!(out, "  {:>14.4}", inference.ln_likelihood)?;
+            Ok(inference.capped.contains(&true))
+        };
+
+        write!(out, "{:>6}", "game")?;
+        for color in &colors {
+            write!(out, "  {color:>8} ")?;
+        }
+        writeln!(out, "  {:>14}", "ln likelihood")?;
+        let mut capped = false;
+        for game in games {
+            capped |= row(out, &game.id.to_string(), &[game])?;
+        }
+        capped |= row(out, "all", &games.iter().collect::<Vec<_>>())?;
+        if capped {
+            writeln!(
+                out,
+                "+ set by --max-cubes {}, not the reveals; a larger cap may change it",
+                self.max_cubes
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn report_query(games: &[Game], query: &Query, out: &mut dyn Write) -> Result<()> {
+    let colors = palette(games);
+    let matching: Vec<&Game> = games.iter().filter(|game| query.matches(game)).collect();
+    let ids: Vec<String> = matching.iter().map(|game| game.id.to_string()).collect();
+    let power = matching.iter().try_fold(0u32, |acc, game| {
+        game.fewest()
+            .power(colors.iter().copied())
+            .and_then(|power| acc.checked_add(power))
+            .ok_or_else(|| Error::overflow(format!("power of game {}", game.id)))
+    })?;
+    writeln!(out, "games: {}", ids.join(" "))?;
+    writeln!(out, "count: {}", matching.len())?;
+    writeln!(out, "sum of ids: {}", sum_ids(matching.into_iter())?)?;
+    writeln!(out, "sum of powers: {power}")?;
+    Ok(())
+}
+
+fn sum_ids<'a>(mut games: impl Iterator<Item = &'a Game>) -> Result<u32> {
+    games.try_fold(0u32, |acc, game| {
+        acc.checked_add(game.id)
+            .ok_or_else(|| Error::overflow("sum of game ids"))
+    })
+}
+
+impl Solution for Day02 {
+    type Model = Vec<Game>;
+    type Answer = Tally;
+
+    const REPORT_ONLY: &'static [&'static str] = &["infer", "max-cubes", "query"];
+
+    fn configure(opts: &mut Options) -> Result<Day02> {
+        let mut bags = Vec::new();
+        for path in opts.values("bags")? {
+            let path = Path::new(&path);
+            let text = fs::read_to_string(path).map_err(|e| {
+                Error::Io(std::io::Error::new(
+                    e.kind(),
+                    format!("cannot read bags {}: {e}", path.display()),
+                ))
+            })?;
+            bags.extend(parse_bags(&text, bags.len() + 1)?);
+        }
+        for bag in opts.values("bag")? {
+            bags.extend(parse_bags(&bag, bags.len() + 1)?);
+        }
+        let mut day = Day02::default();
+        if !bags.is_empty() {
+            day.bags = bags;
+        }
+        day.duplicates = opts.parsed("duplicates")?.unwrap_or_default();
+        day.infer = opts.parsed("infer")?;
+        if let Some(max_cubes) = opts.parsed("max-cubes")? {
+            day.max_cubes = max_cubes;
+        }
+        if let Some(query) = opts.value("query")? {
+            if day.infer.is_some() {
+                return Err(Error::usage(
+                    "--query and --infer ask for different reports",
+                ));
+            }
+            day.query = Some(Query::parse(&query)?);
+        }
+        Ok(day)
+    }
+
+    fn parse(&self, input: &str) -> Result<Vec<Game>> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                let (id, reveals) = parse::line(i + 1, line, parse_game)?;
+                self.duplicates.game(i + 1, id, reveals)
+            })
+            .collect()
+    }
+
+    fn warnings(&self, games: &Vec<Game>) -> Vec<String> {
+        let merge = match self.duplicates {
+            Duplicates::Sum => "adding them up",
+            Duplicates::Max => "keeping the largest",
+            Duplicates::Error => return Vec::new(),
+        };
+        games
+            .iter()
+            .flat_map(|game| {
+                game.repeated.iter().map(move |color| {
+                    format!(
+                        "game {} names {color} twice in one reveal; {merge}",
+                        game.id
+                    )
+                })
+            })
+            .collect()
+    }
+
+    fn part1(&self, games: &Vec<Game>) -> Result<Tally> {
+        let sums = self
+            .bags
+            .iter()
+            .map(|(name, bag)| {
+                let sum = sum_ids(games.iter().filter(|game| game.possible(bag)))?;
+                Ok((name.clone(), sum))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(match &sums[..] {
+            [(_, sum)] => Tally::One(*sum),
+            _ => Tally::PerBag(sums),
+        })
+    }
+
+    /// A game that never shows some color seen elsewhere has power 0.
+    fn part2(&self, games: &Vec<Game>) -> Result<Tally> {
+        let colors = palette(games);
+        let sum = games.iter().try_fold(0u32, |acc, game| {
+            game.fewest()
+                .power(colors.iter().copied())
+                .and_then(|power| acc.checked_add(power))
+                .ok_or_else(|| Error::overflow(format!("power of game {}", game.id)))
+        })?;
+        Ok(Tally::One(sum))
+    }
+
+    /// Part 1 marks which games each bag allows; part 2 lists the fewest
+    /// cubes of each color every game needs, and its power. With `--infer`,
+    /// either part estimates each game's bag instead, then one for them all;
+    /// with `--query`, it lists the matching games and totals for them.
+    fn report(&self, games: &Vec<Game>, part: Part, out: &mut dyn Write) -> Result<()> {
+        if let Some(estimate) = self.infer {
+            return self.report_inference(games, estimate, out);
+        }
+        if let Some(query) = &self.query {
+            return report_query(games, query, out);
+        }
+        match part {
+            Part::One => {
+                write!(out, "{:>6}", "game")?;
+                for (name, _) in &self.bags {
+                    write!(out, "  {name:>8}")?;
+                }
+                writeln!(out)?;
+                for game in games {
+                    write!(out, "{:>6}", game.id)?;
+                    for (_, bag) in &self.bags {
+                        let possible = if game.possible(bag) { "yes" } else { "no" };
+                        write!(out, "  {possible:>8}")?;
+                    }
+                    writeln!(out)?;
+                }
+                write!(out, "{:>6}", "sum")?;
+                for (_, bag) in &self.bags {
+                    let sum = sum_ids(games.iter().filter(|game| game.possible(bag)))?;
+                    write!(out, "  {sum:>8}")?;
+                }
+                writeln!(out)?;
+            }
+            Part::Two => {
+                let colors = palette(games);
+                write!(out, "{:>6}", "game")?;
+                for color in &colors {
+                    write!(out, "  {color:>8}")?;
+                }
+                writeln!(out, "  {:>10}", "power")?;
+                let mut sum = 0u32;
+                for game in games {
+                    let fewest = game.fewest();
+                    write!(out, "{:>6}", game.id)?;
+                    for color in &colors {
+                        write!(out, "  {:>8}", fewest.get(color))?;
+                    }
+                    let power = fewest
+                        .power(colors.iter().copied())
+                        .ok_or_else(|| Error::overflow(format!("power of game {}", game.id)))?;
+                    writeln!(out, "  {power:>10}")?;
+                    sum = sum
+                        .checked_add(power)
+                        .ok_or_else(|| Error::overflow("sum of powers"))?;
+                }
+                let width = 10 * colors.len() + 10;
+                writeln!(out, "{:>6}  {sum:>width$}", "sum")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+";
+
+    #[test]
+    fn sample() {
+        let games = Day02::default().parse(SAMPLE).unwrap();
+        assert_eq!(Day02::default().part1(&games).unwrap(), Tally::One(8));
+        assert_eq!(Day02::default().part2(&games).unwrap(), Tally::One(2286));
+    }
+
+    #[test]
+    fn limits_are_inclusive() {
+        let games = Day02::default()
+            .parse("Game 7: 12 red, 13 green, 14 blue")
+            .unwrap();
+        assert_eq!(Day02::default().part1(&games).unwrap(), Tally::One(7));
+    }
+
+    #[test]
+    fn any_color() {
+        let games = Day02::default()
+            .parse("Game 1: 2 yellow, 1 red\nGame 2: 3 purple; 1 yellow\nGame 3: 1 red")
+            .unwrap();
+        assert_eq!(palette(&games), BTreeSet::from(["purple", "red", "yellow"]));
+        assert_eq!(
+            games[1].fewest(),
+            Reveal::from([("purple", 3), ("yellow", 1)])
+        );
+        assert_eq!(Day02::default().part1(&games).unwrap(), Tally::One(3));
+        assert_eq!(Day02::default().part2(&games).unwrap(), Tally::One(0));
+        let bag = Reveal::from([("yellow", 2), ("red", 1)]);
+        assert!(games[0].possible(&bag) && !games[1].possible(&bag));
+
+        let games = Day02::default()
+            .parse("Game 1: 3 grün, 1 light-blue")
+            .unwrap();
+        assert_eq!(
+            games[0].fewest(),
+            Reveal::from([("grün", 3), ("light-blue", 1)])
+        );
+        let bags = parse_bags("grün=3, light-blue=1", 1).unwrap();
+        assert!(games[0].possible(&bags[0].1));
+    }
+
+    #[test]
+    fn malformed_cubes() {
+        let Err(Error::Parse { line, col, .. }) =
+            Day02::default().parse("Game 1: 3 red\nGame 2: 4 r3d")
+        else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((line, col), (2, 9));
+        let err = Day02::default().parse("Game 1: 3 red, 4").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected a cube count and color, like `3 blue` on line 1, col 16, found `4`"
+        );
+        let Err(Error::Parse { col, .. }) = Day02::default().parse("Game 1: 3 red; 2 blue, x")
+        else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(col, 24);
+    }
+
+    #[test]
+    fn parses_games() {
+        let games = Day02::default()
+            .parse("Game 3: 1 red, 2 blue; 4 green")
+            .unwrap();
+        let reveals = [
+            Reveal::from([("red", 1), ("blue", 2)]),
+            Reveal::from([("green", 4)]),
+        ];
+        assert_eq!(games[0].id, 3);
+        assert_eq!(games[0].reveals, reveals);
+        assert_eq!(games[0].fewest().power(palette(&games)), Some(8));
+    }
+
+    #[test]
+    fn several_bags() {
+        let bags = parse_bags(
+            "# candidates\nsmall: red=1, blue=1\nred=20,green=20,blue=20",
+            1,
+        );
+        let day = Day02 {
+            bags: bags.unwrap(),
+            ..Day02::default()
+        };
+        assert_eq!(day.bags[1].0, "bag 2");
+        let games = day.parse(SAMPLE).unwrap();
+        let sums = day.part1(&games).unwrap();
+        assert_eq!(sums.to_string(), "small: 0, bag 2: 15");
+
+        let mut out = Vec::new();
+        day.report(&games, Part::One, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let rows: Vec<Vec<_>> = out
+            .lines()
+            .map(|l| l.split_whitespace().collect())
+            .collect();
+        assert_eq!(rows[3], ["3", "no", "yes"]);
+        assert_eq!(rows[6], ["sum", "0", "15"]);
+    }
+
+    #[test]
+    fn malformed_bags() {
+        assert!(parse_bags("red=1, red=2", 1).is_err());
+        let Err(Error::Parse { line, col, .. }) = parse_bags("\nred=1, blue", 1) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((line, col), (2, 6));
+    }
+
+    #[test]
+    fn duplicate_colors() {
+        let input = "Game 1: 3 red, 2 red; 1 blue\nGame 2: 13 red";
+        let mut day = Day02::default();
+        let games = day.parse(input).unwrap();
+        assert_eq!(games[0].reveals[0], Reveal::from([("red", 5)]));
+        assert_eq!(day.part2(&games).unwrap(), Tally::One(5));
+        assert_eq!(
+            day.warnings(&games),
+            ["game 1 names red twice in one reveal; adding them up"]
+        );
+        let mut out = Vec::new();
+        let warnings =
+            crate::report::<Day02>(input, Part::Two, &Options::default(), &mut out).unwrap();
+        assert_eq!(warnings, day.warnings(&games));
+
+        day.duplicates = "max".parse().unwrap();
+        let games = day.parse("Game 1: 12 red, 2 red, 7 red").unwrap();
+        assert_eq!(games[0].fewest(), Reveal::from([("red", 12)]));
+        assert_eq!(day.part1(&games).unwrap(), Tally::One(1));
+        assert_eq!(day.warnings(&games).len(), 1);
+
+        day.duplicates = Duplicates::Error;
+        assert!(matches!(day.parse(input), Err(Error::Invalid(_))));
+    }
+
+    #[test]
+    fn infers_bags() {
+        let games = Day02::default()
+            .parse("Game 1: 2 red, 1 blue; 1 red, 2 blue\nGame 2: 3 red")
+            .unwrap();
+        let colors = ["blue", "red"];
+        let first = [&games[0]];
+        let mle = infer(&first, &colors, 10, Estimate::MaxLikelihood).unwrap();
+        assert_eq!(mle.bag, [2.0, 2.0]);
+        assert_eq!(mle.capped, [false, false]);
+        // Each reveal draws 3 of 4 cubes: 2 of the 4 possible handfuls.
+        assert!((mle.ln_likelihood - 2.0 * f64::ln(0.5)).abs() < 1e-9);
+
+        let all: Vec<_> = games.iter().collect();
+        let joint = infer(&all, &colors, 10, Estimate::MaxLikelihood).unwrap();
+        assert!(joint.bag[0] >= 2.0 && joint.bag[1] >= 3.0);
+
+        let mean = infer(&first, &colors, 10, Estimate::PosteriorMean).unwrap();
+        assert!(mean.bag.iter().all(|&n| n > 2.0 && n < 10.0));
+        assert!(mean.ln_likelihood < mle.ln_likelihood);
+        assert_eq!(mean.capped, [false, false]);
+
+        assert!(infer(&all, &colors, 2, Estimate::MaxLikelihood).is_err());
+    }
+
+    #[test]
+    fn likelihood_rising_to_the_bound() {
+        // Two of one color, then two of the other: the bigger the bag, the
+        // likelier, so the best bag is wherever --max-cubes stops the search.
+        let games = Day02::default().parse("Game 1: 2 red; 2 blue").unwrap();
+        let colors = ["blue", "red"];
+        let mle = infer(&[&games[0]], &colors, 10, Estimate::MaxLikelihood).unwrap();
+        assert!(mle.bag.contains(&10.0));
+        assert!(mle.capped.contains(&true));
+        let mean = infer(&[&games[0]], &colors, 10, Estimate::PosteriorMean).unwrap();
+        assert_eq!(mean.capped, [true, true]);
+
+        let day = Day02 {
+            infer: Some(Estimate::MaxLikelihood),
+            max_cubes: 10,
+            ..Day02::default()
+        };
+        let mut out = Vec::new();
+        day.report(&games, Part::One, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("      10+"));
+        assert!(
+            out.ends_with("+ set by --max-cubes 10, not the reveals; a larger cap may change it\n")
+        );
+
+        // Reveals that need the whole cap are not held back by it.
+        let games = Day02::default().parse("Game 1: 3 red, 1 blue").unwrap();
+        let mle = infer(&[&games[0]], &colors, 3, Estimate::MaxLikelihood).unwrap();
+        assert_eq!((mle.bag, mle.capped), (vec![1.0, 3.0], vec![false, false]));
+    }
+
+    #[test]
+    fn rejects_huge_caps() {
+        let games = Day02::default().parse("Game 1: 4294967295 red").unwrap();
+        let max = u32::MAX;
+        for estimate in [Estimate::MaxLikelihood, Estimate::PosteriorMean] {
+            let inference = infer(&[&games[0]], &["red"], max, estimate);
+            assert!(matches!(inference, Err(Error::Usage(_))));
+        }
+    }
+}
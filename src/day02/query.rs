@@ -0,0 +1,322 @@
+//! A small language for picking out games, such as
+//! `max(red) > 10 and max(blue) <= 3` or `any(red > green and total < 5)`.
+//!
+//! A game-level value is a number, `id`, `reveals` (how many there were), or
+//! `max(..)`, `min(..)` or `sum(..)` of a reveal-level value over the game's
+//! reveals. A reveal-level value is a color's count in one reveal, or
+//! `total`, the cubes in that reveal. `any(..)` and `all(..)` test a
+//! condition against each reveal, where both kinds of value may be used.
+//! Conditions compare two values with `<`, `<=`, `>`, `>=`, `==` or `!=`,
+//! and combine with `not`, `and`, `or` and parentheses.
+
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{char, digit1, multispace0},
+    combinator::{map, map_res, value, verify},
+    error::context,
+    multi::separated_list1,
+    sequence::{delimited, pair, preceded, tuple},
+};
+
+use super::{parse_color, Game, Reveal};
+use crate::parse::{self, PResult};
+use crate::{Error, Result};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Query {
+    Or(Vec<Query>),
+    And(Vec<Query>),
+    Not(Box<Query>),
+    Any(Box<Query>),
+    All(Box<Query>),
+    Compare(Value, Op, Value),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Op {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Aggregate {
+    Max,
+    Min,
+    Sum,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Value {
+    Number(u64),
+    Id,
+    Reveals,
+    Total,
+    Count(String),
+    Over(Aggregate, Box<Value>),
+}
+
+const KEYWORDS: [&str; 8] = ["and", "or", "not", "any", "all", "max", "min", "sum"];
+
+fn ws<'a, T>(
+    parser: impl FnMut(&'a str) -> PResult<'a, T>,
+) -> impl FnMut(&'a str) -> PResult<'a, T> {
+    delimited(multispace0, parser, multispace0)
+}
+
+fn keyword<'a>(word: &'static str) -> impl FnMut(&'a str) -> PResult<'a, &'a str> {
+    ws(verify(parse_color, move |w: &str| w == word))
+}
+
+fn parens<'a, T>(
+    parser: impl FnMut(&'a str) -> PResult<'a, T>,
+) -> impl FnMut(&'a str) -> PResult<'a, T> {
+    delimited(ws(char('(')), parser, context("`)`", ws(char(')'))))
+}
+
+fn parse_value(input: &str) -> PResult<'_, Value> {
+    let aggregate = alt((
+        value(Aggregate::Max, keyword("max")),
+        value(Aggregate::Min, keyword("min")),
+        value(Aggregate::Sum, keyword("sum")),
+    ));
+    let word = ws(verify(parse_color, |w: &str| !KEYWORDS.contains(&w)));
+    context(
+        "a number, color, `total`, `id`, `reveals`, or `max`, `min` or `sum`",
+        alt((
+            map(ws(map_res(digit1, str::parse)), Value::Number),
+            map(pair(aggregate, parens(parse_value)), |(agg, v)| {
+                Value::Over(agg, Box::new(v))
+            }),
+            map(word, |w| match w {
+                "id" => Value::Id,
+                "reveals" => Value::Reveals,
+                "total" => Value::Total,
+                color => Value::Count(color.to_string()),
+            }),
+        )),
+    )(input)
+}
+
+fn parse_op(input: &str) -> PResult<'_, Op> {
+    context(
+        "a comparison (`<`, `<=`, `>`, `>=`, `==` or `!=`)",
+        ws(alt((
+            value(Op::Le, tag("<=")),
+            value(Op::Ge, tag(">=")),
+            value(Op::Eq, tag("==")),
+            value(Op::Ne, tag("!=")),
+            value(Op::Lt, tag("<")),
+            value(Op::Gt, tag(">")),
+        ))),
+    )(input)
+}
+
+fn parse_atom(input: &str) -> PResult<'_, Query> {
+    alt((
+        parens(parse_or),
+        map(preceded(keyword("any"), parens(parse_or)), |q| {
+            Query::Any(Box::new(q))
+        }),
+        map(preceded(keyword("all"), parens(parse_or)), |q| {
+            Query::All(Box::new(q))
+        }),
+        map(tuple((parse_value, parse_op, parse_value)), |(a, op, b)| {
+            Query::Compare(a, op, b)
+        }),
+    ))(input)
+}
+
+fn parse_not(input: &str) -> PResult<'_, Query> {
+    alt((
+        map(preceded(keyword("not"), parse_not), |q| {
+            Query::Not(Box::new(q))
+        }),
+        parse_atom,
+    ))(input)
+}
+
+fn parse_and(input: &str) -> PResult<'_, Query> {
+    map(separated_list1(keyword("and"), parse_not), |mut qs| {
+        if qs.len() == 1 {
+            qs.remove(0)
+        } else {
+            Query::And(qs)
+        }
+    })(input)
+}
+
+fn parse_or(input: &str) -> PResult<'_, Query> {
+    map(separated_list1(keyword("or"), parse_and), |mut qs| {
+        if qs.len() == 1 {
+            qs.remove(0)
+        } else {
+            Query::Or(qs)
+        }
+    })(input)
+}
+
+impl Value {
+    /// Whether this needs a reveal to be evaluated against.
+    fn per_reveal(&self) -> bool {
+        matches!(self, Value::Total | Value::Count(_))
+    }
+
+    fn check(&self) -> Result<()> {
+        match self {
+            Value::Over(_, v) if !v.per_reveal() => Err(Error::invalid(format!(
+                "`{v}` is the same for every reveal, so there is nothing to aggregate"
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    fn eval(&self, game: &Game, reveal: Option<&Reveal>) -> u64 {
+        match self {
+            Value::Number(n) => *n,
+            Value::Id => game.id.into(),
+            Value::Reveals => game.reveals.len() as u64,
+            Value::Total => reveal.map_or(0, |r| r.0.values().map(|&n| u64::from(n)).sum()),
+            Value::Count(color) => reveal.map_or(0, |r| r.get(color).into()),
+            Value::Over(agg, v) => {
+                let values = game.reveals.iter().map(|r| v.eval(game, Some(r)));
+                match agg {
+                    Aggregate::Max => values.max().unwrap_or(0),
+                    Aggregate::Min => values.min().unwrap_or(0),
+                    Aggregate::Sum => values.fold(0, u64::saturating_add),
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Number(n) => write!(f, "{n}"),
+            Value::Id => f.write_str("id"),
+            Value::Reveals => f.write_str("reveals"),
+            Value::Total => f.write_str("total"),
+            Value::Count(color) => f.write_str(color),
+            Value::Over(agg, v) => {
+                let agg = match agg {
+                    Aggregate::Max => "max",
+                    Aggregate::Min => "min",
+                    Aggregate::Sum => "sum",
+                };
+                write!(f, "{agg}({v})")
+            }
+        }
+    }
+}
+
+impl Query {
+    pub fn parse(text: &str) -> Result<Query> {
+        let query = parse::line(1, text, parse_or)?;
+        query.check(false)?;
+        Ok(query)
+    }
+
+    /// Rejects reveal-level values outside `any`, `all` or an aggregate.
+    fn check(&self, in_reveal: bool) -> Result<()> {
+        match self {
+            Query::Or(qs) | Query::And(qs) => qs.iter().try_for_each(|q| q.check(in_reveal)),
+            Query::Not(q) => q.check(in_reveal),
+            Query::Any(q) | Query::All(q) => q.check(true),
+            Query::Compare(a, _, b) => [a, b].into_iter().try_for_each(|v| {
+                v.check()?;
+                if v.per_reveal() && !in_reveal {
+                    return Err(Error::invalid(format!(
+                        "`{v}` differs from reveal to reveal; use it inside max, min, sum, any or all"
+                    )));
+                }
+                Ok(())
+            }),
+        }
+    }
+
+    pub fn matches(&self, game: &Game) -> bool {
+        self.eval(game, None)
+    }
+
+    fn eval(&self, game: &Game, reveal: Option<&Reveal>) -> bool {
+        match self {
+            Query::Or(qs) => qs.iter().any(|q| q.eval(game, reveal)),
+            Query::And(qs) => qs.iter().all(|q| q.eval(game, reveal)),
+            Query::Not(q) => !q.eval(game, reveal),
+            Query::Any(q) => game.reveals.iter().any(|r| q.eval(game, Some(r))),
+            Query::All(q) => game.reveals.iter().all(|r| q.eval(game, Some(r))),
+            Query::Compare(a, op, b) => {
+                let (a, b) = (a.eval(game, reveal), b.eval(game, reveal));
+                match op {
+                    Op::Lt => a < b,
+                    Op::Le => a <= b,
+                    Op::Gt => a > b,
+                    Op::Ge => a >= b,
+                    Op::Eq => a == b,
+                    Op::Ne => a != b,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Day02, Game};
+    use super::*;
+    use crate::Solution;
+
+    fn games() -> Vec<Game> {
+        Day02::default()
+            .parse(
+                "Game 1: 11 red, 2 blue; 1 red\n\
+                 Game 2: 3 red, 5 blue\n\
+                 Game 3: 1 green; 2 green; 3 green",
+            )
+            .unwrap()
+    }
+
+    fn ids(query: &str) -> Vec<u32> {
+        let query = Query::parse(query).unwrap();
+        games()
+            .iter()
+            .filter(|game| query.matches(game))
+            .map(|game| game.id)
+            .collect()
+    }
+
+    #[test]
+    fn aggregates() {
+        assert_eq!(ids("max(red) > 10 and max(blue) <= 3"), [1]);
+        assert_eq!(ids("min(red) == 0"), [3]);
+        assert_eq!(ids("sum(green) >= 6 or id == 2"), [2, 3]);
+        assert_eq!(ids("not (reveals > 1)"), [2]);
+    }
+
+    #[test]
+    fn per_reveal() {
+        assert_eq!(ids("any(blue > red)"), [2]);
+        assert_eq!(ids("all(total < 4)"), [3]);
+        assert_eq!(ids("any(total == 13 and id < 5)"), [1]);
+        assert_eq!(ids("max(grün) == 0 and max(light-blue) == 0"), [1, 2, 3]);
+    }
+
+    #[test]
+    fn rejects_bad_queries() {
+        assert!(matches!(Query::parse("red > 3"), Err(Error::Invalid(_))));
+        assert!(matches!(
+            Query::parse("max(id) > 3"),
+            Err(Error::Invalid(_))
+        ));
+        let Err(Error::Parse { col, .. }) = Query::parse("max(red) >") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(col, 11);
+        assert!(Query::parse("max(red > 3").is_err());
+    }
+}
@@ -0,0 +1,430 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::str::FromStr;
+
+use crate::grid::{Grid, Loc, Token};
+use crate::{json, Error, Options, Part, Result, Solution};
+
+pub struct Schematic {
+    grid: Grid<char>,
+    numbers: Vec<(Token, u32)>,
+}
+
+fn is_symbol(c: &char) -> bool {
+    *c != '.' && !c.is_ascii_digit()
+}
+
+/// Which part numbers touch which symbols. Every number and every symbol is
+/// a node, touching anything or not; positions are 0-based rows and columns.
+pub struct Graph {
+    pub numbers: Vec<(Token, u32)>,
+    pub symbols: Vec<(Loc, char)>,
+    /// Indices into `numbers` and `symbols`.
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl Schematic {
+    pub fn graph(&self) -> Graph {
+        let symbols: Vec<(Loc, char)> = self
+            .grid
+            .iter()
+            .filter(|(_, c)| is_symbol(c))
+            .map(|(loc, &c)| (loc, c))
+            .collect();
+        let index: HashMap<Loc, usize> = symbols
+            .iter()
+            .enumerate()
+            .map(|(i, &(loc, _))| (loc, i))
+            .collect();
+        let edges = self
+            .numbers
+            .iter()
+            .enumerate()
+            .flat_map(|(n, &(token, _))| {
+                let index = &index;
+                self.grid
+                    .around(token)
+                    .filter_map(move |loc| Some((n, *index.get(&loc)?)))
+            })
+            .collect();
+        Graph {
+            numbers: self.numbers.clone(),
+            symbols,
+            edges,
+        }
+    }
+}
+
+/// How `aoc report 3` writes the graph.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum Format {
+    /// Graphviz, with each node pinned at its place in the schematic.
+    #[default]
+    Dot,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Format> {
+        match s {
+            "dot" => Ok(Format::Dot),
+            "json" => Ok(Format::Json),
+            _ => Err(Error::usage(format!(
+                "--graph expects dot or json, not `{s}`"
+            ))),
+        }
+    }
+}
+
+impl Graph {
+    pub fn write_dot(&self, out: &mut dyn Write) -> Result<()> {
+        let attached =
+            |edge: fn(&(usize, usize)) -> usize, i| self.edges.iter().any(|e| edge(e) == i);
+        let style = |attached| if attached { "solid" } else { "dashed" };
+
+        writeln!(out, "graph schematic {{")?;
+        for (i, &(token, n)) in self.numbers.iter().enumerate() {
+            let Loc(r, c) = token.start;
+            writeln!(
+                out,
+                "  n{i} [label=\"{n}\", shape=box, style={}, pos=\"{c},-{r}!\"];",
+                style(attached(|e| e.0, i))
+            )?;
+        }
+        for (i, &(Loc(r, c), symbol)) in self.symbols.iter().enumerate() {
+            let label = json::string(&symbol.to_string());
+            writeln!(
+                out,
+                "  s{i} [label={label}, shape=circle, style={}, pos=\"{c},-{r}!\"];",
+                style(attached(|e| e.1, i))
+            )?;
+        }
+        for (n, s) in &self.edges {
+            writeln!(out, "  n{n} -- s{s};")?;
+        }
+        writeln!(out, "}}")?;
+        Ok(())
+    }
+
+    pub fn write_json(&self, out: &mut dyn Write) -> Result<()> {
+        let ids = |prefix: char, ids: Vec<usize>| {
+            json::array(
+                ids.into_iter()
+                    .map(|i| json::string(&format!("{prefix}{i}"))),
+            )
+        };
+        let numbers = self.numbers.iter().enumerate().map(|(i, &(token, n))| {
+            let Loc(r, c) = token.start;
+            let symbols = self.edges.iter().filter(|e| e.0 == i).map(|e| e.1);
+            json::object([
+                ("id", json::string(&format!("n{i}"))),
+                ("value", n.to_string()),
+                ("row", r.to_string()),
+                ("col", c.to_string()),
+                ("len", token.len.to_string()),
+                ("symbols", ids('s', symbols.collect())),
+            ])
+        });
+        let symbols = self
+            .symbols
+            .iter()
+            .enumerate()
+            .map(|(i, &(Loc(r, c), symbol))| {
+                let numbers = self.edges.iter().filter(|e| e.1 == i).map(|e| e.0);
+                json::object([
+                    ("id", json::string(&format!("s{i}"))),
+                    ("symbol", json::string(&symbol.to_string())),
+                    ("row", r.to_string()),
+                    ("col", c.to_string()),
+                    ("numbers", ids('n', numbers.collect())),
+                ])
+            });
+        let edges = self.edges.iter().map(|&(n, s)| {
+            json::array([
+                json::string(&format!("n{n}")),
+                json::string(&format!("s{s}")),
+            ])
+        });
+        let graph = json::object([
+            ("numbers", json::array(numbers)),
+            ("symbols", json::array(symbols)),
+            ("edges", json::array(edges)),
+        ]);
+        writeln!(out, "{graph}")?;
+        Ok(())
+    }
+}
+
+/// How many part numbers a symbol must touch to count as a gear.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Arity {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+impl Arity {
+    fn admits(self, n: usize) -> bool {
+        match self {
+            Arity::Exactly(k) => n == k,
+            Arity::AtLeast(k) => n >= k,
+        }
+    }
+}
+
+/// `n` for exactly n numbers, `n+` for at least n.
+impl FromStr for Arity {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Arity> {
+        let arity = match s.strip_suffix('+') {
+            Some(n) => n.parse().map(Arity::AtLeast),
+            None => s.parse().map(Arity::Exactly),
+        };
+        arity.map_err(|_| Error::usage(format!("--arity expects n or n+, not `{s}`")))
+    }
+}
+
+/// How the part numbers around a gear make its ratio.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Combine {
+    Product,
+    Sum,
+    Max,
+}
+
+impl FromStr for Combine {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Combine> {
+        match s {
+            "product" => Ok(Combine::Product),
+            "sum" => Ok(Combine::Sum),
+            "max" => Ok(Combine::Max),
+            _ => Err(Error::usage(format!(
+                "--combine expects product, sum or max, not `{s}`"
+            ))),
+        }
+    }
+}
+
+impl Combine {
+    fn apply(self, numbers: &[u32]) -> Option<u64> {
+        let mut numbers = numbers.iter().map(|&n| u64::from(n));
+        match self {
+            Combine::Product => numbers.try_fold(1u64, u64::checked_mul),
+            Combine::Sum => numbers.try_fold(0u64, u64::checked_add),
+            Combine::Max => Some(numbers.max().unwrap_or(0)),
+        }
+    }
+}
+
+/// Which symbols are gears in part 2, and what each one is worth. The
+/// puzzle's rule: a `*` touching exactly two numbers, worth their product.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Gears {
+    pub symbols: Vec<char>,
+    pub arity: Arity,
+    pub combine: Combine,
+}
+
+impl Default for Gears {
+    fn default() -> Gears {
+        Gears {
+            symbols: vec!['*'],
+            arity: Arity::Exactly(2),
+            combine: Combine::Product,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Day03 {
+    gears: Gears,
+    graph: Format,
+}
+
+impl Solution for Day03 {
+    type Model = Schematic;
+    type Answer = u32;
+
+    const REPORT_ONLY: &'static [&'static str] = &["graph"];
+
+    fn configure(opts: &mut Options) -> Result<Day03> {
+        let mut gears = Gears::default();
+        if let Some(symbols) = opts.value("gear-symbols")? {
+            if let Some(c) = symbols.chars().find(|c| !is_symbol(c)) {
+                return Err(Error::usage(format!(
+                    "`{c}` is not a symbol, so it cannot be a gear"
+                )));
+            }
+            gears.symbols = symbols.chars().collect();
+        }
+        if let Some(arity) = opts.parsed("arity")? {
+            gears.arity = arity;
+        }
+        if let Some(combine) = opts.parsed("combine")? {
+            gears.combine = combine;
+        }
+        let graph = opts.parsed("graph")?.unwrap_or_default();
+        Ok(Day03 { gears, graph })
+    }
+
+    fn parse(&self, input: &str) -> Result<Schematic> {
+        let grid = Grid::parse(input)?;
+        let numbers = grid
+            .tokens(char::is_ascii_digit)
+            .into_iter()
+            .map(|token| {
+                let Loc(r, c) = token.start;
+                let digits: String = token.locs().map(|loc| grid[loc]).collect();
+                let n = digits.parse().map_err(|_| {
+                    Error::parse(r + 1, c + 1, "a part number that fits in 32 bits", &digits)
+                })?;
+                Ok((token, n))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Schematic { grid, numbers })
+    }
+
+    fn part1(&self, Schematic { grid, numbers }: &Schematic) -> Result<u32> {
+        numbers.iter().try_fold(0u32, |acc, &(token, n)| {
+            if grid.around(token).any(|loc| is_symbol(&grid[loc])) {
+                acc.checked_add(n)
+                    .ok_or_else(|| Error::overflow("sum of part numbers"))
+            } else {
+                Ok(acc)
+            }
+        })
+    }
+
+    fn part2(&self, Schematic { grid, numbers }: &Schematic) -> Result<u32> {
+        // Every gear symbol counts, even one touching no numbers at all.
+        let mut gears: HashMap<Loc, Vec<u32>> = grid
+            .iter()
+            .filter(|(_, c)| self.gears.symbols.contains(c))
+            .map(|(loc, _)| (loc, Vec::new()))
+            .collect();
+        for &(token, n) in numbers {
+            for loc in grid.around(token) {
+                if let Some(touching) = gears.get_mut(&loc) {
+                    touching.push(n);
+                }
+            }
+        }
+
+        gears
+            .values()
+            .filter(|numbers| self.gears.arity.admits(numbers.len()))
+            .try_fold(0u32, |acc, numbers| {
+                self.gears
+                    .combine
+                    .apply(numbers)
+                    .and_then(|ratio| u32::try_from(ratio).ok())
+                    .and_then(|ratio| acc.checked_add(ratio))
+                    .ok_or_else(|| Error::overflow("sum of gear ratios"))
+            })
+    }
+
+    /// Either part exports the graph of part numbers and symbols.
+    fn report(&self, schematic: &Schematic, _: Part, out: &mut dyn Write) -> Result<()> {
+        let graph = schematic.graph();
+        match self.graph {
+            Format::Dot => graph.write_dot(out),
+            Format::Json => graph.write_json(out),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..
+";
+
+    #[test]
+    fn sample() {
+        let schematic = Day03::default().parse(SAMPLE).unwrap();
+        assert_eq!(Day03::default().part1(&schematic).unwrap(), 4361);
+        assert_eq!(Day03::default().part2(&schematic).unwrap(), 467835);
+    }
+
+    #[test]
+    fn gear_touching_three_numbers() {
+        let schematic = Day03::default().parse("1.2\n.*.\n3..").unwrap();
+        assert_eq!(Day03::default().part1(&schematic).unwrap(), 6);
+        assert_eq!(Day03::default().part2(&schematic).unwrap(), 0);
+    }
+
+    #[test]
+    fn numbers_at_edges() {
+        let schematic = Day03::default().parse("12*\n...\n*34").unwrap();
+        assert_eq!(Day03::default().part1(&schematic).unwrap(), 46);
+    }
+
+    #[test]
+    fn part_number_too_big() {
+        let Err(Error::Parse { line, col, .. }) =
+            Day03::default().parse(".............\n.99999999999*")
+        else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((line, col), (2, 2));
+    }
+
+    #[test]
+    fn gear_rules() {
+        let schematic = Day03::default().parse("1.2\n.*.\n3#4").unwrap();
+        let day = |symbols: &str, arity: &str, combine: &str| Day03 {
+            gears: Gears {
+                symbols: symbols.chars().collect(),
+                arity: arity.parse().unwrap(),
+                combine: combine.parse().unwrap(),
+            },
+            ..Day03::default()
+        };
+        assert_eq!(day("*", "2", "product").part2(&schematic).unwrap(), 0);
+        assert_eq!(day("*", "3+", "product").part2(&schematic).unwrap(), 24);
+        assert_eq!(day("*", "4", "sum").part2(&schematic).unwrap(), 10);
+        assert_eq!(day("#", "2", "max").part2(&schematic).unwrap(), 4);
+        assert_eq!(day("*#", "2+", "sum").part2(&schematic).unwrap(), 17);
+        assert!("2-".parse::<Arity>().is_err());
+
+        let lone = Day03::default().parse("1*.\n..*").unwrap();
+        assert_eq!(day("*", "0", "product").part2(&lone).unwrap(), 1);
+        assert_eq!(day("*", "0+", "sum").part2(&lone).unwrap(), 1);
+        assert_eq!(day("*", "1+", "max").part2(&lone).unwrap(), 1);
+    }
+
+    #[test]
+    fn graph_keeps_loose_nodes() {
+        let schematic = Day03::default().parse("12.#\n.*..\n...5").unwrap();
+        let graph = schematic.graph();
+        assert_eq!(graph.symbols, [(Loc(0, 3), '#'), (Loc(1, 1), '*')]);
+        assert_eq!(graph.edges, [(0, 1)]);
+
+        let mut dot = Vec::new();
+        graph.write_dot(&mut dot).unwrap();
+        let dot = String::from_utf8(dot).unwrap();
+        assert!(dot.contains("n1 [label=\"5\", shape=box, style=dashed, pos=\"3,-2!\"];"));
+        assert!(dot.contains("s0 [label=\"#\", shape=circle, style=dashed"));
+        assert!(dot.contains("n0 -- s1;"));
+
+        let mut json = Vec::new();
+        graph.write_json(&mut json).unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert!(json.contains(r#"{"id":"n1","value":5,"row":2,"col":3,"len":1,"symbols":[]}"#));
+        assert!(json.contains(r#""edges":[["n0","s1"]]"#));
+    }
+}
@@ -0,0 +1,202 @@
+use nom::{
+    bytes::complete::tag,
+    character::complete::{self, digit1},
+    combinator::map_res,
+    error::context,
+    multi::{many0, many1},
+    sequence::tuple,
+};
+
+use crate::parse::{self, PResult};
+use crate::{Error, Result, Solution};
+
+fn parse_num(input: &str) -> PResult<'_, u32> {
+    let (input, _) = many0(complete::char(' '))(input)?;
+    context("a number", map_res(digit1, str::parse))(input)
+}
+
+#[derive(Debug)]
+pub struct Card {
+    num: u32,
+    winning_nums: Vec<u32>,
+    my_nums: Vec<u32>,
+}
+
+fn parse_card(input: &str) -> PResult<'_, Card> {
+    let (input, (_, card_num, _)) = tuple((
+        context("`Card`", tag("Card")),
+        context("card number", parse_num),
+        context("`:` after card number", tag(":")),
+    ))(input)?;
+    let (input, mut winning_nums) = context("winning numbers", many1(parse_num))(input)?;
+    let (input, _) = context("` | ` after winning numbers", tag(" | "))(input)?;
+    let (input, mut my_nums) = context("numbers you have", many1(parse_num))(input)?;
+
+    winning_nums.sort();
+    my_nums.sort();
+
+    Ok((
+        input,
+        Card {
+            num: card_num,
+            winning_nums,
+            my_nums,
+        },
+    ))
+}
+
+fn calc_score(
+    winning_nums: &mut dyn Iterator<Item = u32>,
+    my_nums: &mut dyn Iterator<Item = u32>,
+    points: u32,
+) -> Option<u32> {
+    let mut winning_nums = winning_nums.peekable();
+    let mut my_nums = my_nums.peekable();
+    if let (Some(&w), Some(&m)) = (winning_nums.peek(), my_nums.peek()) {
+        if w > m {
+            calc_score(&mut winning_nums, &mut my_nums.skip(1), points)
+        } else if w < m {
+            calc_score(&mut winning_nums.skip(1), &mut my_nums, points)
+        } else {
+            calc_score(
+                &mut winning_nums,
+                &mut my_nums.skip(1),
+                if points == 0 {
+                    1
+                } else {
+                    points.checked_mul(2)?
+                },
+            )
+        }
+    } else {
+        Some(points)
+    }
+}
+
+fn count_matches(
+    winning_nums: &mut dyn Iterator<Item = u32>,
+    my_nums: &mut dyn Iterator<Item = u32>,
+    matches: u32,
+) -> u32 {
+    let mut winning_nums = winning_nums.peekable();
+    let mut my_nums = my_nums.peekable();
+    if let (Some(&w), Some(&m)) = (winning_nums.peek(), my_nums.peek()) {
+        if w > m {
+            count_matches(&mut winning_nums, &mut my_nums.skip(1), matches)
+        } else if w < m {
+            count_matches(&mut winning_nums.skip(1), &mut my_nums, matches)
+        } else {
+            count_matches(&mut winning_nums, &mut my_nums.skip(1), matches + 1)
+        }
+    } else {
+        matches
+    }
+}
+
+#[derive(Default)]
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Model = Vec<Card>;
+    type Answer = u32;
+
+    fn parse(&self, input: &str) -> Result<Vec<Card>> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                let card = parse::line(i + 1, line, parse_card)?;
+                if card.num as usize != i + 1 {
+                    return Err(Error::invalid(format!(
+                        "card {} found on line {}; cards must be numbered from 1 in order",
+                        card.num,
+                        i + 1
+                    )));
+                }
+                Ok(card)
+            })
+            .collect()
+    }
+
+    fn part1(&self, cards: &Vec<Card>) -> Result<u32> {
+        cards
+            .iter()
+            .try_fold(0u32, |acc, card| {
+                acc.checked_add(calc_score(
+                    &mut card.winning_nums.iter().copied(),
+                    &mut card.my_nums.iter().copied(),
+                    0,
+                )?)
+            })
+            .ok_or_else(|| Error::overflow("total points"))
+    }
+
+    fn part2(&self, cards: &Vec<Card>) -> Result<u32> {
+        let mut card_counts: Vec<u32> = vec![1; cards.len()];
+        for (card_num, card) in cards.iter().enumerate() {
+            let matches = count_matches(
+                &mut card.winning_nums.iter().copied(),
+                &mut card.my_nums.iter().copied(),
+                0,
+            ) as usize;
+            let count_of_card_num = card_counts[card_num];
+            let won = card_counts
+                .get_mut(card_num + 1..card_num + 1 + matches)
+                .ok_or_else(|| {
+                    Error::invalid(format!("card {} wins copies past the last card", card.num))
+                })?;
+            for n in won {
+                *n = n
+                    .checked_add(count_of_card_num)
+                    .ok_or_else(|| Error::overflow("number of scratchcards"))?;
+            }
+        }
+        card_counts
+            .iter()
+            .try_fold(0u32, |acc, &n| acc.checked_add(n))
+            .ok_or_else(|| Error::overflow("number of scratchcards"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+";
+
+    #[test]
+    fn sample() {
+        let cards = Day04.parse(SAMPLE).unwrap();
+        assert_eq!(Day04.part1(&cards).unwrap(), 13);
+        assert_eq!(Day04.part2(&cards).unwrap(), 30);
+    }
+
+    #[test]
+    fn missing_separator() {
+        let err = Day04.parse("Card 1: 41 48 83 86 17 83 86").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected ` | ` after winning numbers on line 1, col 29, found end of line"
+        );
+    }
+
+    #[test]
+    fn winning_past_last_card() {
+        let cards = Day04.parse("Card 1: 1 2 | 1 2").unwrap();
+        assert!(matches!(Day04.part2(&cards), Err(Error::Invalid(_))));
+    }
+
+    #[test]
+    fn points_overflow() {
+        let nums: Vec<String> = (1..=33).map(|n| n.to_string()).collect();
+        let nums = nums.join(" ");
+        let cards = Day04.parse(&format!("Card 1: {nums} | {nums}")).unwrap();
+        assert!(matches!(Day04.part1(&cards), Err(Error::Overflow(_))));
+    }
+}
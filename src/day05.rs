@@ -0,0 +1,410 @@
+use std::iter::Peekable;
+use std::str;
+
+use nom::{
+    bytes::complete::tag,
+    character::complete::digit1,
+    combinator::{map_res, success},
+    error::context,
+    multi::many1,
+    sequence::{preceded, tuple},
+};
+
+use crate::parse::{self, PResult};
+use crate::{Error, Result, Solution};
+
+#[derive(Clone, Debug)]
+struct Map(Vec<(u64, u64, u64)>);
+
+// To avoid overflow panic
+const MAX: u64 = u64::MAX / 2;
+
+fn _merge(
+    v: &[(u64, u64, u64)],
+    w: &[(u64, u64, u64)],
+    mut dest: Vec<(u64, u64, u64)>,
+) -> Vec<(u64, u64, u64)> {
+    let (Some(&(d1, s1, l1)), Some(&(d2, s2, l2))) = (v.first(), w.first()) else {
+        return dest;
+    };
+
+    if d1 + l1 <= s2 {
+        _merge(&v[1..], w, dest)
+    } else if s2 + l2 <= d1 {
+        _merge(v, &w[1..], dest)
+    } else if v.len() == 1 {
+        let lb = d1.max(s2);
+        let ub = (d1 + l1).min(s2 + l2);
+        let s3 = s1 + lb - d1;
+        let d3 = d2 + lb - s2;
+        let l3 = ub - lb;
+        dest.push((d3, s3, l3));
+        _merge(v, &w[1..], dest)
+    } else {
+        _merge(&v[1..], w, _merge(&v[0..1], w, dest))
+    }
+}
+
+impl Map {
+    fn add(&mut self, dest_start: u64, src_start: u64, range_len: u64) {
+        self.0.push((dest_start, src_start, range_len));
+    }
+
+    fn get(&self, src: u64) -> u64 {
+        for &(dest_start, src_start, range_len) in &self.0 {
+            if (src_start..(src_start + range_len)).contains(&src) {
+                return src - src_start + dest_start;
+            }
+        }
+        src
+    }
+
+    fn complete(&mut self) {
+        let v = &mut self.0;
+        v.sort_by_key(|&(_, s, _)| s);
+        let mut additional: Vec<(u64, u64, u64)> = Vec::new();
+
+        let first = v.first().map_or(0, |&(_, s, _)| s);
+        if first > 0 {
+            additional.push((0, 0, first));
+        }
+
+        v.windows(2).for_each(|w| {
+            let ((_, s1, l1), (_, s2, _)) = (w[0], w[1]);
+            if s1 + l1 < s2 {
+                additional.push((s1 + l1, s1 + l1, s2 - (s1 + l1)))
+            }
+        });
+
+        let last = v.last().map_or(0, |&(_, s, l)| s + l);
+        if last <= MAX {
+            additional.push((last, last, MAX - last + 1))
+        }
+
+        v.append(&mut additional);
+    }
+
+    fn merge(&mut self, other: &mut Map) -> Map {
+        let v = &mut self.0;
+        v.sort_by_key(|&(d, _, _)| d);
+
+        let w = &mut other.0;
+        w.sort_by_key(|&(_, s, _)| s);
+
+        Map(_merge(v, w, Vec::new()))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Almanac {
+    seeds: Vec<u64>,
+    seed_soil_map: Map,
+    soil_fertilizer_map: Map,
+    fertilizer_water_map: Map,
+    water_light_map: Map,
+    light_temperature_map: Map,
+    temperature_humidity_map: Map,
+    humidity_location_map: Map,
+}
+
+impl Almanac {
+    fn get_lowest_loc(&self) -> Option<u64> {
+        let mut min_location: Option<u64> = None;
+
+        for &seed in &self.seeds {
+            let soil = self.seed_soil_map.get(seed);
+            let fertilizer = self.soil_fertilizer_map.get(soil);
+            let water = self.fertilizer_water_map.get(fertilizer);
+            let light = self.water_light_map.get(water);
+            let temperature = self.light_temperature_map.get(light);
+            let humidity = self.temperature_humidity_map.get(temperature);
+            let location = self.humidity_location_map.get(humidity);
+
+            min_location = Some(min_location.map_or(location, |min| min.min(location)));
+        }
+
+        min_location
+    }
+
+    fn complete(&mut self) {
+        self.seed_soil_map.complete();
+        self.soil_fertilizer_map.complete();
+        self.fertilizer_water_map.complete();
+        self.water_light_map.complete();
+        self.light_temperature_map.complete();
+        self.temperature_humidity_map.complete();
+        self.humidity_location_map.complete();
+    }
+
+    fn merge_all(&mut self) -> Map {
+        self.seed_soil_map
+            .merge(&mut self.soil_fertilizer_map)
+            .merge(&mut self.fertilizer_water_map)
+            .merge(&mut self.water_light_map)
+            .merge(&mut self.light_temperature_map)
+            .merge(&mut self.temperature_humidity_map)
+            .merge(&mut self.humidity_location_map)
+    }
+}
+
+struct Lines<'a> {
+    lines: Peekable<str::Lines<'a>>,
+    line: usize,
+}
+
+impl<'a> Lines<'a> {
+    fn new(input: &'a str) -> Lines<'a> {
+        Lines {
+            lines: input.lines().peekable(),
+            line: 0,
+        }
+    }
+
+    /// Parses the next line with `parser`, which must consume all of it.
+    fn parse<T>(
+        &mut self,
+        expected: &str,
+        parser: impl FnMut(&'a str) -> PResult<'a, T>,
+    ) -> Result<T> {
+        self.line += 1;
+        let line = self
+            .lines
+            .next()
+            .ok_or_else(|| Error::end_of_input(self.line, expected))?;
+        parse::line(self.line, line, parser)
+    }
+
+    /// Fails on the first non-blank line left over.
+    fn finish(&mut self) -> Result<()> {
+        for line in self.lines.by_ref() {
+            self.line += 1;
+            if !line.trim().is_empty() {
+                return Err(Error::parse(self.line, 1, "end of input", line));
+            }
+        }
+        Ok(())
+    }
+
+    fn peek(&mut self) -> Option<&'a str> {
+        self.lines.peek().copied()
+    }
+
+    fn blank(&mut self) -> Result<()> {
+        self.parse("a blank line", success(()))
+    }
+}
+
+fn parse_number(input: &str) -> PResult<'_, u64> {
+    context("a number", map_res(digit1, str::parse))(input)
+}
+
+/// Part 2 reads the seeds as start/length pairs, so when they pair up,
+/// each range must end within `MAX` too.
+fn parse_seeds(input: &mut Lines) -> Result<Vec<u64>> {
+    let seeds: Vec<u64> = input.parse(
+        "the seeds line",
+        preceded(
+            context("`seeds:`", tag("seeds:")),
+            many1(preceded(tag(" "), parse_number)),
+        ),
+    )?;
+
+    let ranges = seeds.len().is_multiple_of(2).then(|| seeds.chunks(2));
+    let too_big = seeds.iter().any(|&seed| seed > MAX)
+        || ranges.into_iter().flatten().any(|ch| ch[0] + ch[1] > MAX);
+    if too_big {
+        return Err(Error::overflow(format!(
+            "seeds on line {} exceed {MAX}",
+            input.line
+        )));
+    }
+
+    Ok(seeds)
+}
+
+fn parse_entry(input: &mut Lines) -> Result<(u64, u64, u64)> {
+    let (a, _, b, _, c) = input.parse(
+        "a map entry",
+        tuple((
+            parse_number,
+            context("` `", tag(" ")),
+            parse_number,
+            context("` `", tag(" ")),
+            parse_number,
+        )),
+    )?;
+
+    if a.max(b).checked_add(c).is_none_or(|end| end > MAX) {
+        return Err(Error::overflow(format!(
+            "map entry on line {} exceeds {MAX}",
+            input.line
+        )));
+    }
+
+    Ok((a, b, c))
+}
+
+/// `header` is quoted in backticks, the way parse errors show it.
+fn parse_map(input: &mut Lines, header: &'static str) -> Result<Map> {
+    input.parse(header, context(header, tag(header.trim_matches('`'))))?;
+
+    let mut m = Map(Vec::new());
+    while input.peek().is_some_and(|line| !line.is_empty()) {
+        let (a, b, c) = parse_entry(input)?;
+        m.add(a, b, c);
+    }
+    Ok(m)
+}
+
+fn parse_almanac(input: &mut Lines) -> Result<Almanac> {
+    let seeds = parse_seeds(input)?;
+    input.blank()?;
+    let seed_soil_map = parse_map(input, "`seed-to-soil map:`")?;
+    input.blank()?;
+    let soil_fertilizer_map = parse_map(input, "`soil-to-fertilizer map:`")?;
+    input.blank()?;
+    let fertilizer_water_map = parse_map(input, "`fertilizer-to-water map:`")?;
+    input.blank()?;
+    let water_light_map = parse_map(input, "`water-to-light map:`")?;
+    input.blank()?;
+    let light_temperature_map = parse_map(input, "`light-to-temperature map:`")?;
+    input.blank()?;
+    let temperature_humidity_map = parse_map(input, "`temperature-to-humidity map:`")?;
+    input.blank()?;
+    let humidity_location_map = parse_map(input, "`humidity-to-location map:`")?;
+    input.finish()?;
+    Ok(Almanac {
+        seeds,
+        seed_soil_map,
+        soil_fertilizer_map,
+        fertilizer_water_map,
+        water_light_map,
+        light_temperature_map,
+        temperature_humidity_map,
+        humidity_location_map,
+    })
+}
+
+#[derive(Default)]
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Model = Almanac;
+    type Answer = u64;
+
+    fn parse(&self, input: &str) -> Result<Almanac> {
+        parse_almanac(&mut Lines::new(input))
+    }
+
+    fn part1(&self, almanac: &Almanac) -> Result<u64> {
+        almanac
+            .get_lowest_loc()
+            .ok_or_else(|| Error::invalid("the almanac lists no seeds"))
+    }
+
+    fn part2(&self, almanac: &Almanac) -> Result<u64> {
+        if !almanac.seeds.len().is_multiple_of(2) {
+            return Err(Error::invalid(
+                "seed ranges must come in start/length pairs",
+            ));
+        }
+
+        let mut almanac = almanac.clone();
+
+        almanac.complete();
+
+        let mut seeds = Map(almanac
+            .seeds
+            .chunks(2)
+            .map(|ch| (ch[0], ch[0], ch[1]))
+            .collect());
+
+        let mut m = seeds.merge(&mut almanac.merge_all());
+        m.0.sort_by_key(|&(d, _, _)| d);
+
+        let first =
+            m.0.first()
+                .ok_or_else(|| Error::invalid("the almanac lists no seeds"))?;
+
+        Ok(first.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4
+";
+
+    #[test]
+    fn sample() {
+        let almanac = Day05.parse(SAMPLE).unwrap();
+        assert_eq!(Day05.part1(&almanac).unwrap(), 35);
+        assert_eq!(Day05.part2(&almanac).unwrap(), 46);
+    }
+
+    #[test]
+    fn odd_seed_count() {
+        let almanac = Day05.parse(&SAMPLE.replacen("13", "13 7", 1)).unwrap();
+        assert!(matches!(Day05.part2(&almanac), Err(Error::Invalid(_))));
+    }
+
+    #[test]
+    fn seed_range_overflow() {
+        let input = SAMPLE.replacen("79 14", "18446744073709551000 1000", 1);
+        assert!(matches!(Day05.parse(&input), Err(Error::Overflow(_))));
+    }
+
+    #[test]
+    fn wrong_map_header() {
+        let err = Day05
+            .parse(&SAMPLE.replace("water-to-light", "water-to-lite"))
+            .unwrap_err();
+        let Error::Parse { line, col, .. } = err else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((line, col), (18, 1));
+    }
+
+    #[test]
+    fn trailing_garbage() {
+        assert!(Day05.parse(&format!("{SAMPLE}\n\n")).is_ok());
+        let Err(Error::Parse { line, .. }) = Day05.parse(&format!("{SAMPLE}\ngarbage here")) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(line, 35);
+    }
+}
@@ -0,0 +1,136 @@
+use nom::{
+    bytes::complete::tag,
+    character::complete::{digit1, multispace1},
+    error::context,
+    multi::separated_list1,
+    sequence::tuple,
+};
+
+use crate::parse::{self, PResult};
+use crate::{Error, Result, Solution};
+
+pub struct Sheet {
+    times: Vec<String>,
+    dists: Vec<String>,
+}
+
+/// `label` is quoted in backticks, the way parse errors show it.
+fn parse_row<'a>(label: &'static str) -> impl FnMut(&'a str) -> PResult<'a, Vec<String>> {
+    move |input| {
+        let (input, _) = tuple((
+            context(label, tag(label.trim_matches('`'))),
+            context("whitespace", multispace1),
+        ))(input)?;
+        let (input, result) = context("numbers", separated_list1(multispace1, digit1))(input)?;
+        Ok((input, result.into_iter().map(String::from).collect()))
+    }
+}
+
+fn number(digits: &str) -> Result<u64> {
+    digits
+        .parse()
+        .map_err(|_| Error::overflow(format!("{digits} does not fit in 64 bits")))
+}
+
+fn ways_to_win(t: u64, d: u64) -> u64 {
+    let beats = |hold: u64| hold as u128 * (t - hold) as u128 > d as u128;
+    let tf = t as f64;
+    let df = d as f64;
+    let disc = f64::sqrt(tf * tf - 4.0 * df);
+    if disc.is_nan() {
+        return 0;
+    }
+
+    // The float root is only a guess: nudge it onto the shortest winning hold,
+    // which must beat the record rather than tie it.
+    let mut c1 = (((tf - disc) / 2.0).floor() as u64).min(t / 2);
+    while c1 <= t / 2 && !beats(c1) {
+        c1 += 1;
+    }
+    while c1 > 0 && beats(c1 - 1) {
+        c1 -= 1;
+    }
+
+    if c1 > t / 2 {
+        0
+    } else {
+        t - 2 * c1 + 1
+    }
+}
+
+#[derive(Default)]
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Model = Sheet;
+    type Answer = u64;
+
+    fn parse(&self, input: &str) -> Result<Sheet> {
+        let mut input = input.lines();
+        let mut row = |line: usize, label| match input.next() {
+            Some(row) => parse::line(line, row, parse_row(label)),
+            None => Err(Error::end_of_input(line, label)),
+        };
+        let times = row(1, "`Time:`")?;
+        let dists = row(2, "`Distance:`")?;
+        if let Some((i, line)) = (3..).zip(input).find(|(_, line)| !line.trim().is_empty()) {
+            return Err(Error::parse(i, 1, "end of input", line));
+        }
+        if times.len() != dists.len() {
+            return Err(Error::invalid(
+                "every race needs both a time and a distance",
+            ));
+        }
+        Ok(Sheet { times, dists })
+    }
+
+    fn part1(&self, Sheet { times, dists }: &Sheet) -> Result<u64> {
+        times.iter().zip(dists).try_fold(1u64, |a, (t, d)| {
+            a.checked_mul(ways_to_win(number(t)?, number(d)?))
+                .ok_or_else(|| Error::overflow("product of ways to win"))
+        })
+    }
+
+    fn part2(&self, Sheet { times, dists }: &Sheet) -> Result<u64> {
+        Ok(ways_to_win(
+            number(&times.join(""))?,
+            number(&dists.join(""))?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "Time:      7  15   30
+Distance:  9  40  200
+";
+
+    #[test]
+    fn sample() {
+        let sheet = Day06.parse(SAMPLE).unwrap();
+        assert_eq!(Day06.part1(&sheet).unwrap(), 288);
+        assert_eq!(Day06.part2(&sheet).unwrap(), 71503);
+    }
+
+    #[test]
+    fn trailing_garbage() {
+        assert!(Day06.parse(&format!("{SAMPLE}\n")).is_ok());
+        let Err(Error::Parse { line, .. }) = Day06.parse(&format!("{SAMPLE}junk")) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(line, 3);
+    }
+
+    #[test]
+    fn record_on_exact_root_does_not_count() {
+        assert_eq!(ways_to_win(30, 200), 9);
+        assert_eq!(ways_to_win(4, 4), 0);
+    }
+
+    #[test]
+    fn unbeatable_record() {
+        assert_eq!(ways_to_win(3, 100), 0);
+    }
+}
@@ -0,0 +1,98 @@
+use std::fmt::Display;
+use std::io;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    /// The input does not follow the puzzle's format. `line` and `col` are
+    /// 1-based; `found` is the text at that position, or `None` at the end of
+    /// the input.
+    Parse {
+        line: usize,
+        col: usize,
+        expected: String,
+        found: Option<String>,
+    },
+    /// The input parses but cannot be solved, e.g. a line without any digit.
+    Invalid(String),
+    /// An intermediate value does not fit in the answer type.
+    Overflow(String),
+    /// A command-line option is unknown or malformed.
+    Usage(String),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// How much of the offending input a parse error quotes.
+const SNIPPET_LEN: usize = 16;
+
+impl Error {
+    pub fn parse(line: usize, col: usize, expected: impl Into<String>, found: &str) -> Error {
+        Error::Parse {
+            line,
+            col,
+            expected: expected.into(),
+            found: Some(found.chars().take(SNIPPET_LEN).collect()),
+        }
+    }
+
+    pub fn end_of_input(line: usize, expected: impl Into<String>) -> Error {
+        Error::Parse {
+            line,
+            col: 1,
+            expected: expected.into(),
+            found: None,
+        }
+    }
+
+    pub fn invalid(msg: impl Into<String>) -> Error {
+        Error::Invalid(msg.into())
+    }
+
+    pub fn overflow(msg: impl Into<String>) -> Error {
+        Error::Overflow(msg.into())
+    }
+
+    pub fn usage(msg: impl Into<String>) -> Error {
+        Error::Usage(msg.into())
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "{e}"),
+            Error::Parse {
+                line,
+                col,
+                expected,
+                found,
+            } => {
+                write!(f, "expected {expected} on line {line}, col {col}")?;
+                match found.as_deref() {
+                    None => write!(f, ", found end of input"),
+                    Some("") => write!(f, ", found end of line"),
+                    Some(found) => write!(f, ", found `{found}`"),
+                }
+            }
+            Error::Invalid(msg) => write!(f, "invalid input: {msg}"),
+            Error::Overflow(msg) => write!(f, "overflow: {msg}"),
+            Error::Usage(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
+    }
+}
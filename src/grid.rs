@@ -0,0 +1,184 @@
+use std::ops::Index;
+
+use crate::{Error, Result};
+
+/// A cell position: 0-based row, then column.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Loc(pub usize, pub usize);
+
+/// A rectangular grid of cells, stored row by row.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+/// A horizontal run of cells: `len` cells starting at `start`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Token {
+    pub start: Loc,
+    pub len: usize,
+}
+
+impl Token {
+    pub fn locs(&self) -> impl Iterator<Item = Loc> {
+        let Loc(r, c) = self.start;
+        (c..c + self.len).map(move |c| Loc(r, c))
+    }
+
+    pub fn contains(&self, Loc(r, c): Loc) -> bool {
+        let Loc(row, col) = self.start;
+        r == row && (col..col + self.len).contains(&c)
+    }
+}
+
+impl Grid<char> {
+    /// One row per line; every line must be as wide as the first.
+    pub fn parse(input: &str) -> Result<Grid<char>> {
+        let mut cells = Vec::with_capacity(input.len());
+        let mut width = None;
+        let mut height = 0;
+        for (i, line) in input.lines().enumerate() {
+            let before = cells.len();
+            cells.extend(line.chars());
+            let len = cells.len() - before;
+            match width {
+                None => width = Some(len),
+                Some(width) if width != len => {
+                    return Err(Error::invalid(format!(
+                        "line {} is {len} wide, but line 1 is {width}",
+                        i + 1
+                    )))
+                }
+                Some(_) => {}
+            }
+            height += 1;
+        }
+        Ok(Grid {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, Loc(r, c): Loc) -> Option<&T> {
+        (r < self.height && c < self.width).then(|| &self.cells[r * self.width + c])
+    }
+
+    pub fn row(&self, r: usize) -> &[T] {
+        &self.cells[r * self.width..(r + 1) * self.width]
+    }
+
+    pub fn column(&self, c: usize) -> impl Iterator<Item = &T> {
+        assert!(c < self.width, "column {c} is outside the grid");
+        self.cells.iter().skip(c).step_by(self.width)
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Loc, &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| (Loc(i / width, i % width), cell))
+    }
+
+    /// The up to eight cells touching `loc`, diagonals included.
+    pub fn neighbours(&self, Loc(r, c): Loc) -> impl Iterator<Item = Loc> + '_ {
+        let rows = r.saturating_sub(1)..=(r + 1).min(self.height.saturating_sub(1));
+        rows.flat_map(move |row| {
+            let cols = c.saturating_sub(1)..=(c + 1).min(self.width.saturating_sub(1));
+            cols.map(move |col| Loc(row, col))
+        })
+        .filter(move |&loc| loc != Loc(r, c))
+    }
+
+    /// The cells touching `token` but outside it, diagonals included.
+    pub fn around(&self, token: Token) -> impl Iterator<Item = Loc> + '_ {
+        let Loc(r, c) = token.start;
+        let rows = r.saturating_sub(1)..=(r + 1).min(self.height.saturating_sub(1));
+        rows.flat_map(move |row| {
+            let cols = c.saturating_sub(1)..=(c + token.len).min(self.width.saturating_sub(1));
+            cols.map(move |col| Loc(row, col))
+        })
+        .filter(move |&loc| !token.contains(loc))
+    }
+
+    /// The maximal horizontal runs of cells satisfying `pred`, row by row.
+    pub fn tokens(&self, pred: impl Fn(&T) -> bool) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        for r in 0..self.height {
+            let mut run: Option<Token> = None;
+            for (c, cell) in self.row(r).iter().enumerate() {
+                match (&mut run, pred(cell)) {
+                    (Some(token), true) => token.len += 1,
+                    (None, true) => {
+                        run = Some(Token {
+                            start: Loc(r, c),
+                            len: 1,
+                        })
+                    }
+                    (_, false) => tokens.extend(run.take()),
+                }
+            }
+            tokens.extend(run);
+        }
+        tokens
+    }
+}
+
+impl<T> Index<Loc> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, loc: Loc) -> &T {
+        self.get(loc)
+            .unwrap_or_else(|| panic!("{loc:?} is outside the grid"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indexes_rows_and_columns() {
+        let grid = Grid::parse("abc\ndef").unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Loc(1, 2)], 'f');
+        assert_eq!(grid.get(Loc(2, 0)), None);
+        assert_eq!(grid.row(1), ['d', 'e', 'f']);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        let outside = std::panic::catch_unwind(|| grid.column(4).count());
+        assert!(outside.is_err());
+        assert!(Grid::parse("abc\nde").is_err());
+    }
+
+    #[test]
+    fn neighbours_stay_inside() {
+        let grid = Grid::parse("...\n...\n...").unwrap();
+        assert_eq!(grid.neighbours(Loc(0, 0)).count(), 3);
+        assert_eq!(grid.neighbours(Loc(1, 1)).count(), 8);
+        assert_eq!(grid.neighbours(Loc(2, 1)).count(), 5);
+    }
+
+    #[test]
+    fn tokens_and_their_surroundings() {
+        let grid = Grid::parse("12.3\n.456").unwrap();
+        let tokens = grid.tokens(char::is_ascii_digit);
+        let starts: Vec<_> = tokens.iter().map(|t| (t.start, t.len)).collect();
+        assert_eq!(starts, [(Loc(0, 0), 2), (Loc(0, 3), 1), (Loc(1, 1), 3)]);
+        assert_eq!(grid.around(tokens[0]).count(), 4);
+        assert_eq!(grid.around(tokens[2]).count(), 5);
+    }
+}
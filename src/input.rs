@@ -0,0 +1,128 @@
+use std::fmt::Display;
+use std::fs;
+use std::io::{self, stdin, BufRead, BufReader, ErrorKind, Read};
+use std::path::PathBuf;
+
+use crate::Result;
+
+pub const INPUT_DIR: &str = "input";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    Stdin,
+    File(PathBuf),
+}
+
+impl Source {
+    /// The checked-in puzzle input for `day`, e.g. `input/day-05`.
+    pub fn for_day(day: u32) -> Source {
+        Source::File(PathBuf::from(INPUT_DIR).join(format!("day-{day:02}")))
+    }
+
+    /// Interprets an `--input` argument, where `-` means stdin.
+    pub fn from_arg(arg: &str) -> Source {
+        if arg == "-" {
+            Source::Stdin
+        } else {
+            Source::File(PathBuf::from(arg))
+        }
+    }
+
+    pub fn read(&self) -> Result<String> {
+        match self {
+            Source::Stdin => {
+                let mut input = String::new();
+                stdin().read_to_string(&mut input)?;
+                Ok(input)
+            }
+            Source::File(path) => fs::read_to_string(path).map_err(|e| self.error(e)),
+        }
+    }
+
+    /// The input as a reader, for solvers that stream it.
+    pub fn open(&self) -> Result<Box<dyn BufRead>> {
+        match self {
+            Source::Stdin => Ok(Box::new(stdin().lock())),
+            Source::File(path) => match fs::File::open(path) {
+                Ok(file) => Ok(Box::new(BufReader::new(file))),
+                Err(e) => Err(self.error(e)),
+            },
+        }
+    }
+
+    fn error(&self, e: io::Error) -> crate::Error {
+        let msg = if e.kind() == ErrorKind::NotFound {
+            format!("input file {self} not found (use --input <path>, or - for stdin)")
+        } else {
+            format!("cannot read input file {self}: {e}")
+        };
+        io::Error::new(e.kind(), msg).into()
+    }
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Stdin => write!(f, "-"),
+            Source::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(hash, |h, &b| (h ^ b as u64).wrapping_mul(0x100000001b3))
+}
+
+fn show_hash(hash: u64) -> String {
+    format!("fnv1a64:{hash:016x}")
+}
+
+/// FNV-1a hash of the input, so results can be tied to the exact input used.
+pub fn hash(input: &str) -> String {
+    show_hash(fnv1a(FNV_OFFSET, input.as_bytes()))
+}
+
+/// Passes a reader through, hashing what is read from it as [`hash`] would.
+pub struct Hashing<R> {
+    inner: R,
+    hash: u64,
+}
+
+impl<R: BufRead> Hashing<R> {
+    pub fn new(inner: R) -> Hashing<R> {
+        Hashing {
+            inner,
+            hash: FNV_OFFSET,
+        }
+    }
+
+    /// The hash of everything read so far.
+    pub fn hash(&self) -> String {
+        show_hash(self.hash)
+    }
+}
+
+impl<R: BufRead> Read for Hashing<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hash = fnv1a(self.hash, &buf[..n]);
+        Ok(n)
+    }
+}
+
+impl<R: BufRead> BufRead for Hashing<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if let Ok(buf) = self.inner.fill_buf() {
+            self.hash = fnv1a(self.hash, &buf[..amt]);
+        }
+        self.inner.consume(amt);
+    }
+}
@@ -0,0 +1,204 @@
+use std::fmt::Display;
+use std::io::{BufRead, Write};
+use std::time::{Duration, Instant};
+
+pub mod answers;
+pub mod bench;
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod error;
+pub mod grid;
+pub mod input;
+pub mod json;
+pub mod options;
+pub mod parse;
+
+pub use error::{Error, Result};
+pub use options::Options;
+
+/// A puzzle solver: `parse` builds the day's model from the raw input, and
+/// each part computes its answer from that model. `Default` solves the
+/// puzzle as published; `configure` applies day-specific options.
+pub trait Solution: Default {
+    type Model;
+    type Answer: Display;
+
+    /// Options that only change `report`, so `solve` turns them away.
+    const REPORT_ONLY: &'static [&'static str] = &[];
+
+    fn configure(opts: &mut Options) -> Result<Self> {
+        let _ = opts;
+        Ok(Self::default())
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Model>;
+    fn part1(&self, model: &Self::Model) -> Result<Self::Answer>;
+    fn part2(&self, model: &Self::Model) -> Result<Self::Answer>;
+
+    /// Things in the model worth pointing out that are not errors.
+    fn warnings(&self, model: &Self::Model) -> Vec<String> {
+        let _ = model;
+        Vec::new()
+    }
+
+    /// Answers `parts` in one pass over `input`, for days that need not hold
+    /// the whole input in memory.
+    fn stream(&self, input: &mut dyn BufRead, parts: &[Part]) -> Result<Vec<Self::Answer>> {
+        let _ = (input, parts);
+        Err(Error::usage("this day cannot stream its input"))
+    }
+
+    /// Writes how `part`'s answer comes about, piece by piece, to `out`.
+    fn report(&self, model: &Self::Model, part: Part, out: &mut dyn Write) -> Result<()> {
+        let _ = (model, part, out);
+        Err(Error::usage("this day has no report"))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::One => f.pad("1"),
+            Part::Two => f.pad("2"),
+        }
+    }
+}
+
+/// The answers from one solver invocation, with how long each phase took.
+#[derive(Debug)]
+pub struct Run {
+    pub parse: Duration,
+    pub answers: Vec<(Part, String, Duration)>,
+    pub warnings: Vec<String>,
+}
+
+pub type Solver = fn(&str, &[Part], &Options) -> Result<Run>;
+
+/// Parses the input once and answers each requested part from the same model.
+pub fn solve<S: Solution>(input: &str, parts: &[Part], opts: &Options) -> Result<Run> {
+    opts.report_only(S::REPORT_ONLY)?;
+    let mut opts = opts.clone();
+    let solution = S::configure(&mut opts)?;
+    opts.finish()?;
+
+    let start = Instant::now();
+    let model = solution.parse(input)?;
+    let parse = start.elapsed();
+    let answers = parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let ans = match part {
+                Part::One => solution.part1(&model)?.to_string(),
+                Part::Two => solution.part2(&model)?.to_string(),
+            };
+            Ok((part, ans, start.elapsed()))
+        })
+        .collect::<Result<_>>()?;
+    let warnings = solution.warnings(&model);
+    Ok(Run {
+        parse,
+        answers,
+        warnings,
+    })
+}
+
+/// The answers from one streamed pass over the input. Reading, parsing and
+/// answering all happen together, so only the whole pass is timed.
+#[derive(Debug)]
+pub struct Streamed {
+    pub pass: Duration,
+    pub answers: Vec<(Part, String)>,
+}
+
+pub type Streamer = fn(&mut dyn BufRead, &[Part], &Options) -> Result<Streamed>;
+
+/// Answers each requested part straight from `input`.
+pub fn stream<S: Solution>(
+    input: &mut dyn BufRead,
+    parts: &[Part],
+    opts: &Options,
+) -> Result<Streamed> {
+    opts.report_only(S::REPORT_ONLY)?;
+    let mut opts = opts.clone();
+    let solution = S::configure(&mut opts)?;
+    opts.finish()?;
+
+    let start = Instant::now();
+    let answers = solution.stream(input, parts)?;
+    Ok(Streamed {
+        pass: start.elapsed(),
+        answers: parts
+            .iter()
+            .zip(answers)
+            .map(|(&part, ans)| (part, ans.to_string()))
+            .collect(),
+    })
+}
+
+pub type Reporter = fn(&str, Part, &Options, &mut dyn Write) -> Result<Vec<String>>;
+
+/// Parses the input and writes the report for one part, returning the
+/// model's warnings as `solve` does.
+pub fn report<S: Solution>(
+    input: &str,
+    part: Part,
+    opts: &Options,
+    out: &mut dyn Write,
+) -> Result<Vec<String>> {
+    let mut opts = opts.clone();
+    let solution = S::configure(&mut opts)?;
+    opts.finish()?;
+    let model = solution.parse(input)?;
+    solution.report(&model, part, out)?;
+    Ok(solution.warnings(&model))
+}
+
+pub const DAYS: [(u32, Solver); 6] = [
+    (1, solve::<day01::Day01>),
+    (2, solve::<day02::Day02>),
+    (3, solve::<day03::Day03>),
+    (4, solve::<day04::Day04>),
+    (5, solve::<day05::Day05>),
+    (6, solve::<day06::Day06>),
+];
+
+pub fn find_day(day: u32) -> Option<Solver> {
+    DAYS.iter()
+        .find(|(n, _)| *n == day)
+        .map(|&(_, solver)| solver)
+}
+
+/// The days that `aoc run` feeds from a reader instead of a string.
+pub const STREAMS: [(u32, Streamer); 1] = [(1, stream::<day01::Day01>)];
+
+pub fn find_stream(day: u32) -> Option<Streamer> {
+    STREAMS
+        .iter()
+        .find(|(n, _)| *n == day)
+        .map(|&(_, stream)| stream)
+}
+
+/// The days that can explain their answers with `aoc report`.
+pub const REPORTS: [(u32, Reporter); 3] = [
+    (1, report::<day01::Day01>),
+    (2, report::<day02::Day02>),
+    (3, report::<day03::Day03>),
+];
+
+pub fn find_report(day: u32) -> Option<Reporter> {
+    REPORTS
+        .iter()
+        .find(|(n, _)| *n == day)
+        .map(|&(_, report)| report)
+}
@@ -1,3 +1,330 @@
+use std::env;
+use std::io;
+use std::iter::Peekable;
+use std::path::PathBuf;
+use std::process::exit;
+use std::time::Duration;
+
+use advent_of_code_2023::answers::{Answers, ANSWERS_FILE};
+use advent_of_code_2023::bench::bench;
+use advent_of_code_2023::input::{self, Source};
+use advent_of_code_2023::json;
+use advent_of_code_2023::{find_day, find_report, find_stream, Error, Options, Part, Solver, DAYS};
+
+const USAGE: &str = "usage: aoc run <day> [--part 1|2|both] [--input <path>|-] [--format text|json]
+       aoc verify [--record] [--answers <path>]
+       aoc bench <day> [--runs <n>] [--input <path>|-] [--csv]
+       aoc report <day> [--part 1|2] [--input <path>|-]
+       aoc list
+
+run --format json times each part (parse_ns, elapsed_ns), except for days
+read in one streamed pass (1), which time only that pass (pass_ns).
+
+day options (run, bench, report):
+  1: --lexicon <path> (replaces one..nine), --words <word=value,...>,
+     --digitless error|skip|zero, --unicode (any decimal digit, any case),
+     --fuzzy <edits> (match misspelled words; fewer than the shortest word)
+  2: --bag [<name>: ]<color>=<n>,... (repeatable), --bags <path> (one bag per line),
+     --duplicates sum|max|error (a color named twice in one reveal),
+     --infer mle|mean (report only; estimate each bag),
+     --max-cubes <n> (report only; default 30),
+     --query <condition> (report only; e.g. \"max(red) > 10 and any(blue < 3)\")
+  3: --gear-symbols <chars> (default *), --arity <n>|<n>+ (default 2),
+     --combine product|sum|max, --graph dot|json (report only; default dot)";
+
+fn fail(msg: &str) -> ! {
+    eprintln!("aoc: {msg}");
+    exit(1)
+}
+
+fn day_arg(args: &mut impl Iterator<Item = String>) -> (u32, Solver) {
+    let day = args.next().unwrap_or_else(|| fail(USAGE));
+    let day: u32 = day
+        .parse()
+        .unwrap_or_else(|_| fail(&format!("invalid day `{day}`")));
+    match find_day(day) {
+        Some(solve) => (day, solve),
+        None => fail(&format!("day {day} is not available (try `aoc list`)")),
+    }
+}
+
+fn input_arg(args: &mut impl Iterator<Item = String>) -> Source {
+    match args.next() {
+        Some(path) => Source::from_arg(&path),
+        None => fail("--input expects a path, or - for stdin"),
+    }
+}
+
+/// Collects `--name[=value]` or `--name value` into `opts`, for the solver.
+fn day_option(opts: &mut Options, arg: &str, args: &mut Peekable<impl Iterator<Item = String>>) {
+    let Some(name) = arg.strip_prefix("--") else {
+        fail(&format!("unexpected argument `{arg}`\n{USAGE}"))
+    };
+    match name.split_once('=') {
+        Some((name, value)) => opts.push(name, Some(value.to_string())),
+        None => opts.push(name, args.next_if(|value| !value.starts_with('-'))),
+    }
+}
+
+fn run(args: impl Iterator<Item = String>) {
+    let mut args = args.peekable();
+    let (day, solve) = day_arg(&mut args);
+    let mut opts = Options::default();
+    let mut parts = vec![Part::One, Part::Two];
+    let mut source = Source::for_day(day);
+    let mut json = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" | "-p" => {
+                parts = match args.next().as_deref() {
+                    Some("1") => vec![Part::One],
+                    Some("2") => vec![Part::Two],
+                    Some("both") => vec![Part::One, Part::Two],
+                    _ => fail("--part expects 1, 2 or both"),
+                }
+            }
+            "--input" | "-i" => source = input_arg(&mut args),
+            "--format" | "-f" => {
+                json = match args.next().as_deref() {
+                    Some("text") => false,
+                    Some("json") => true,
+                    _ => fail("--format expects text or json"),
+                }
+            }
+            _ => day_option(&mut opts, &arg, &mut args),
+        }
+    }
+
+    // Days that can stream read the input once, without holding all of it.
+    // Their answers come out of one pass, so only that pass is timed.
+    let (answers, warnings, hash) = match find_stream(day) {
+        Some(stream) => {
+            let reader = source.open().unwrap_or_else(|e| fail(&e.to_string()));
+            let mut reader = input::Hashing::new(reader);
+            let run = stream(&mut reader, &parts, &opts).unwrap_or_else(|e| fail(&e.to_string()));
+            let pass = run.pass.as_nanos().to_string();
+            let answers: Vec<_> = run
+                .answers
+                .into_iter()
+                .map(|(part, ans)| (part, ans, vec![("pass_ns", pass.clone())]))
+                .collect();
+            (answers, Vec::new(), reader.hash())
+        }
+        None => {
+            let input = source.read().unwrap_or_else(|e| fail(&e.to_string()));
+            let run = solve(&input, &parts, &opts).unwrap_or_else(|e| fail(&e.to_string()));
+            let parse = run.parse.as_nanos().to_string();
+            let answers: Vec<_> = run
+                .answers
+                .into_iter()
+                .map(|(part, ans, elapsed)| {
+                    let timing = vec![
+                        ("parse_ns", parse.clone()),
+                        ("elapsed_ns", elapsed.as_nanos().to_string()),
+                    ];
+                    (part, ans, timing)
+                })
+                .collect();
+            (answers, run.warnings, input::hash(&input))
+        }
+    };
+
+    for warning in &warnings {
+        eprintln!("aoc: warning: {warning}");
+    }
+    if json {
+        for (part, ans, timing) in answers {
+            let fields = [
+                ("day", day.to_string()),
+                ("part", part.to_string()),
+                ("answer", json::string(&ans)),
+                ("input", json::string(&source.to_string())),
+                ("input_hash", json::string(&hash)),
+            ];
+            println!("{}", json::object(fields.into_iter().chain(timing)));
+        }
+    } else if let [(_, ans, _)] = &answers[..] {
+        println!("{ans}");
+    } else {
+        for (part, ans, _) in answers {
+            println!("part {part}: {ans}");
+        }
+    }
+}
+
+fn ms(d: Duration) -> String {
+    format!("{:.3}ms", d.as_secs_f64() * 1000.0)
+}
+
+fn verify(mut args: impl Iterator<Item = String>) {
+    let mut record = false;
+    let mut path = PathBuf::from(ANSWERS_FILE);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--record" => record = true,
+            "--answers" => {
+                path = match args.next() {
+                    Some(path) => PathBuf::from(path),
+                    None => fail("--answers expects a path"),
+                }
+            }
+            _ => fail(&format!("unexpected argument `{arg}`\n{USAGE}")),
+        }
+    }
+
+    let mut answers = Answers::load(&path).unwrap_or_else(|e| fail(&e.to_string()));
+    let mut failed = false;
+
+    println!(
+        "{:>3} {:>4}  {:<6} {:>16} {:>16} {:>12} {:>12}",
+        "day", "part", "status", "answer", "expected", "parse", "solve"
+    );
+    for (day, solve) in DAYS {
+        let run = Source::for_day(day)
+            .read()
+            .and_then(|input| solve(&input, &[Part::One, Part::Two], &Options::default()));
+        let run = match run {
+            Ok(run) => run,
+            Err(e) => {
+                failed = true;
+                println!("{day:>3} {:>4}  {:<6} {e}", "-", "error");
+                continue;
+            }
+        };
+
+        for (part, ans, elapsed) in &run.answers {
+            let expected = answers.get(day, *part).map(String::from);
+            let status = match &expected {
+                Some(expected) if expected == ans => "pass",
+                Some(_) if record => "update",
+                Some(_) => "FAIL",
+                None if record => "record",
+                None => "new",
+            };
+            failed |= status == "FAIL";
+            println!(
+                "{day:>3} {part:>4}  {status:<6} {ans:>16} {:>16} {:>12} {:>12}",
+                expected.as_deref().unwrap_or("-"),
+                ms(run.parse),
+                ms(*elapsed),
+            );
+            if record {
+                answers.record(day, *part, ans);
+            }
+        }
+    }
+
+    if record {
+        answers.save(&path).unwrap_or_else(|e| fail(&e.to_string()));
+    }
+    if failed {
+        exit(1);
+    }
+}
+
+fn bench_day(args: impl Iterator<Item = String>) {
+    let mut args = args.peekable();
+    let (day, solve) = day_arg(&mut args);
+    let mut opts = Options::default();
+    let mut runs = 10;
+    let mut source = Source::for_day(day);
+    let mut csv = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--runs" | "-n" => {
+                runs = match args.next().and_then(|n| n.parse().ok()) {
+                    Some(n) if n > 0 => n,
+                    _ => fail("--runs expects a positive number"),
+                }
+            }
+            "--input" | "-i" => source = input_arg(&mut args),
+            "--csv" => csv = true,
+            _ => day_option(&mut opts, &arg, &mut args),
+        }
+    }
+
+    let input = source.read().unwrap_or_else(|e| fail(&e.to_string()));
+    let stats = bench(solve, &input, &opts, runs).unwrap_or_else(|e| fail(&e.to_string()));
+
+    if csv {
+        println!("day,phase,runs,min_ns,median_ns,max_ns");
+        for (phase, s) in stats {
+            println!(
+                "{day},{phase},{runs},{},{},{}",
+                s.min.as_nanos(),
+                s.median.as_nanos(),
+                s.max.as_nanos()
+            );
+        }
+    } else {
+        println!("day {day}, {runs} runs");
+        println!(
+            "{:<6} {:>12} {:>12} {:>12}",
+            "phase", "min", "median", "max"
+        );
+        for (phase, s) in stats {
+            println!(
+                "{phase:<6} {:>12} {:>12} {:>12}",
+                ms(s.min),
+                ms(s.median),
+                ms(s.max)
+            );
+        }
+    }
+}
+
+fn report(args: impl Iterator<Item = String>) {
+    let mut args = args.peekable();
+    let (day, _) = day_arg(&mut args);
+    let report = find_report(day).unwrap_or_else(|| fail(&format!("day {day} has no report")));
+    let mut opts = Options::default();
+    let mut part = Part::Two;
+    let mut source = Source::for_day(day);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" | "-p" => {
+                part = match args.next().as_deref() {
+                    Some("1") => Part::One,
+                    Some("2") => Part::Two,
+                    _ => fail("--part expects 1 or 2"),
+                }
+            }
+            "--input" | "-i" => source = input_arg(&mut args),
+            _ => day_option(&mut opts, &arg, &mut args),
+        }
+    }
+
+    let input = source.read().unwrap_or_else(|e| fail(&e.to_string()));
+    match report(&input, part, &opts, &mut io::stdout().lock()) {
+        // Reports are long, and often cut short with `head`.
+        Err(Error::Io(e)) if e.kind() == io::ErrorKind::BrokenPipe => {}
+        Err(e) => fail(&e.to_string()),
+        Ok(warnings) => {
+            for warning in &warnings {
+                eprintln!("aoc: warning: {warning}");
+            }
+        }
+    }
+}
+
+fn list() {
+    for (day, _) in DAYS {
+        println!("{day}");
+    }
+}
+
 fn main() {
-    println!("Hello, world!");
+    let mut args = env::args().skip(1);
+    match args.next().as_deref() {
+        Some("run") => run(args),
+        Some("verify") => verify(args),
+        Some("bench") => bench_day(args),
+        Some("report") => report(args),
+        Some("list") => list(),
+        _ => fail(USAGE),
+    }
 }
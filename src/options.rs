@@ -0,0 +1,110 @@
+use crate::{Error, Result};
+
+/// Day-specific command-line options, as `--name value` or `--flag`. Each
+/// solver takes out the ones it understands; anything left is an error.
+#[derive(Clone, Default, Debug)]
+pub struct Options(Vec<(String, Option<String>)>);
+
+impl Options {
+    pub fn push(&mut self, name: impl Into<String>, value: Option<String>) {
+        self.0.push((name.into(), value));
+    }
+
+    fn take(&mut self, name: &str) -> Vec<Option<String>> {
+        let (taken, rest) = self.0.drain(..).partition(|(n, _)| n == name);
+        self.0 = rest;
+        taken.into_iter().map(|(_, value)| value).collect()
+    }
+
+    /// Whether `--name` was given; it must not carry a value.
+    pub fn flag(&mut self, name: &str) -> Result<bool> {
+        let taken = self.take(name);
+        if taken.iter().any(Option::is_some) {
+            return Err(Error::usage(format!("--{name} does not take a value")));
+        }
+        Ok(!taken.is_empty())
+    }
+
+    /// Every value given to `--name`, in order.
+    pub fn values(&mut self, name: &str) -> Result<Vec<String>> {
+        self.take(name)
+            .into_iter()
+            .map(|value| value.ok_or_else(|| Error::usage(format!("--{name} expects a value"))))
+            .collect()
+    }
+
+    /// The value of `--name`; when repeated, the last one wins.
+    pub fn value(&mut self, name: &str) -> Result<Option<String>> {
+        Ok(self.values(name)?.pop())
+    }
+
+    /// Parses the value of `--name` with `FromStr`.
+    pub fn parsed<T: std::str::FromStr>(&mut self, name: &str) -> Result<Option<T>> {
+        self.value(name)?
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| Error::usage(format!("invalid value `{value}` for --{name}")))
+            })
+            .transpose()
+    }
+
+    /// Fails if any of `names` was given, for options that only `aoc report`
+    /// looks at.
+    pub fn report_only(&self, names: &[&str]) -> Result<()> {
+        match self.0.iter().find(|(n, _)| names.contains(&n.as_str())) {
+            Some((name, _)) => Err(Error::usage(format!(
+                "--{name} only applies to `aoc report`"
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Fails on any option no one took.
+    pub fn finish(&self) -> Result<()> {
+        match self.0.first() {
+            Some((name, _)) => Err(Error::usage(format!(
+                "--{name} is not an option for this day"
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn takes_options() {
+        let mut opts = Options::default();
+        opts.push("words", Some("uno=1".to_string()));
+        opts.push("unicode", None);
+        opts.push("words", Some("dos=2".to_string()));
+        opts.push("fuzzy", Some("2".to_string()));
+
+        assert_eq!(opts.values("words").unwrap(), ["uno=1", "dos=2"]);
+        assert!(opts.flag("unicode").unwrap());
+        assert!(!opts.flag("unicode").unwrap());
+        assert_eq!(opts.parsed::<usize>("fuzzy").unwrap(), Some(2));
+        assert!(opts.finish().is_ok());
+    }
+
+    #[test]
+    fn rejects_leftovers() {
+        let mut opts = Options::default();
+        opts.push("bogus", None);
+        assert!(opts.value("bogus").is_err());
+        opts.push("bogus", None);
+        assert!(matches!(opts.finish(), Err(Error::Usage(_))));
+    }
+
+    #[test]
+    fn rejects_report_only() {
+        let mut opts = Options::default();
+        opts.push("bag", Some("red=1".to_string()));
+        assert!(opts.report_only(&["query"]).is_ok());
+        opts.push("query", Some("id > 1".to_string()));
+        assert!(matches!(opts.report_only(&["query"]), Err(Error::Usage(_))));
+    }
+}
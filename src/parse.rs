@@ -0,0 +1,70 @@
+use nom::{
+    combinator::eof,
+    error::{context, ErrorKind, VerboseError, VerboseErrorKind},
+    sequence::terminated,
+    Err, IResult,
+};
+
+use crate::{Error, Result};
+
+/// Parser result whose error keeps every failed combinator and `context`.
+pub type PResult<'a, T> = IResult<&'a str, T, VerboseError<&'a str>>;
+
+/// Runs `parser` over the whole of `text`, the 1-based line `line` of the
+/// input, and turns a failure into an `Error::Parse` pointing at the column
+/// where nom gave up.
+pub fn line<'a, T>(
+    line: usize,
+    text: &'a str,
+    parser: impl FnMut(&'a str) -> PResult<'a, T>,
+) -> Result<T> {
+    terminated(parser, context("end of line", eof))(text)
+        .map(|(_, t)| t)
+        .map_err(|e| located(line, text, e))
+}
+
+/// Converts a nom error raised while parsing `text` into an `Error::Parse`.
+pub fn located<'a>(line: usize, text: &'a str, e: Err<VerboseError<&'a str>>) -> Error {
+    match e {
+        Err::Error(e) | Err::Failure(e) => {
+            let rest = e.errors.first().map_or(text, |&(rest, _)| rest);
+            Error::parse(line, column(text, rest), expected(&e.errors), rest)
+        }
+        Err::Incomplete(_) => Error::end_of_input(line, "more input"),
+    }
+}
+
+/// 1-based character column of `rest`, a suffix of `text`.
+pub fn column(text: &str, rest: &str) -> usize {
+    let offset = text.len().saturating_sub(rest.len());
+    text[..offset].chars().count() + 1
+}
+
+/// Describes what the innermost failing parser wanted: the closest
+/// `context`, or else a description of the nom error itself.
+pub fn expected<I>(errors: &[(I, VerboseErrorKind)]) -> String {
+    errors
+        .iter()
+        .find_map(|(_, kind)| match kind {
+            VerboseErrorKind::Context(ctx) => Some(ctx.to_string()),
+            _ => None,
+        })
+        .or_else(|| {
+            errors.first().map(|(_, kind)| match kind {
+                VerboseErrorKind::Char(c) => format!("`{c}`"),
+                VerboseErrorKind::Nom(kind) => describe(*kind),
+                VerboseErrorKind::Context(ctx) => ctx.to_string(),
+            })
+        })
+        .unwrap_or_else(|| "valid input".to_string())
+}
+
+fn describe(kind: ErrorKind) -> String {
+    match kind {
+        ErrorKind::Digit => "a number".to_string(),
+        ErrorKind::Space | ErrorKind::MultiSpace => "whitespace".to_string(),
+        ErrorKind::Eof => "end of line".to_string(),
+        ErrorKind::MapRes => "a number that fits".to_string(),
+        kind => kind.description().to_lowercase(),
+    }
+}